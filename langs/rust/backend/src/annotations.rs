@@ -1,29 +1,26 @@
-use esexpr::ESExprCodec;
-
-
-#[derive(ESExprCodec, PartialEq, Clone, Debug)]
-pub enum RustAnnRecord {
-	Unit,
-	Tuple,
-	Derive(String),
-}
-
-
-#[derive(ESExprCodec, PartialEq, Clone, Debug)]
-pub enum RustAnnEnum {
-	Derive(String),
-}
-
-
-#[derive(ESExprCodec, PartialEq, Clone, Debug)]
-pub enum RustAnnSimpleEnum {
-	Derive(String),
-}
-
-#[derive(ESExprCodec, PartialEq, Clone, Debug)]
-pub enum RustAnnEnumCase {
-	Unit,
-	Tuple,
+use esexpr::{ESExpr, ESExprCodec};
+use noble_idl_api::annotation_schema::AnnotationTarget;
+use noble_idl_api::{RustAnnEnum, RustAnnEnumCase, RustAnnExternType, RustAnnPackage, RustAnnRecord, RustAnnRecordField, RustAnnSimpleEnum, RustAnnTypeParameter};
+
+
+// The compiler only validates rust annotations when the scope declarations are part of the model,
+// so the backend checks them again against the types it uses.
+pub fn check_rust_annotation(target: AnnotationTarget, value: &ESExpr) -> Result<(), String> {
+	fn decode<T: ESExprCodec>(value: &ESExpr) -> Result<(), String> {
+		T::decode_esexpr(value.clone())
+			.map(|_| ())
+			.map_err(|e| format!("{:?}", e))
+	}
+
+	match target {
+		AnnotationTarget::Package => decode::<RustAnnPackage>(value),
+		AnnotationTarget::Record => decode::<RustAnnRecord>(value),
+		AnnotationTarget::RecordField => decode::<RustAnnRecordField>(value),
+		AnnotationTarget::Enum => decode::<RustAnnEnum>(value),
+		AnnotationTarget::EnumCase => decode::<RustAnnEnumCase>(value),
+		AnnotationTarget::SimpleEnum => decode::<RustAnnSimpleEnum>(value),
+		AnnotationTarget::ExternType => decode::<RustAnnExternType>(value),
+		AnnotationTarget::TypeParameter => decode::<RustAnnTypeParameter>(value),
+		_ => Err(format!("rust annotations are not supported on a {}", target)),
+	}
 }
-
-
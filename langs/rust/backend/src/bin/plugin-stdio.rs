@@ -0,0 +1,18 @@
+use std::io::{BufWriter, Write};
+
+use noble_idl_compiler_rust::emit::emit_from_stream;
+
+
+fn main() {
+	let stdin = std::io::stdin().lock();
+	let mut stdout = BufWriter::new(std::io::stdout().lock());
+
+	let res = emit_from_stream(stdin, &mut stdout)
+		.map_err(|e| format!("{:?}", e))
+		.and_then(|_| stdout.flush().map_err(|e| e.to_string()));
+
+	if let Err(e) = res {
+		eprintln!("Error generating Rust code: {}", e);
+		std::process::exit(1);
+	}
+}
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use noble_idl_compiler::{compile, NobleIDLOptions};
-use noble_idl_compiler_rust::{CrateOptions, Crates, PackageMapping, RustLanguageOptions, RustPlugin};
+use noble_idl_compiler::{compile, lint::LintConfiguration, NobleIDLOptions};
+use noble_idl_compiler_rust::{CrateOptions, Crates, OutputLayout, PackageMapping, RustLanguageOptions, RustPlugin};
 
 
 fn main() {
@@ -25,6 +25,7 @@ fn main() {
 			},
 
 			output_dir: dir.join("../../../runtime/src").into_os_string().into_string().unwrap(),
+			output_layout: OutputLayout::Flat,
 		},
 
 		files: vec![
@@ -33,6 +34,8 @@ fn main() {
 
 		library_files: vec![
 		],
+
+		lints: LintConfiguration::default(),
 	};
 
 
@@ -61,16 +64,20 @@ fn main() {
 			},
 
 			output_dir: dir.join("../../../../../noble-idl-api/src").into_os_string().into_string().unwrap(),
+			output_layout: OutputLayout::Flat,
 		},
 
 		files: vec![
 			dir.join("../../../../noble-idl/backend/compiler-api.nidl"),
 			dir.join("../../../../noble-idl/backend/compiler-api-esexpr-annotations.nidl"),
+			dir.join("../../../../noble-idl/backend/compiler-api-rust-annotations.nidl"),
 		],
 
 		library_files: vec![
 			dir.join("../../../../noble-idl/runtime/nobleidl-core.nidl"),
 		],
+
+		lints: LintConfiguration::default(),
 	};
 
 
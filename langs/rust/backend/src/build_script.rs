@@ -0,0 +1,137 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use derive_more::From;
+use noble_idl_api::NobleIdlDiagnosticSeverity;
+use noble_idl_compiler::{compile, lint::LintConfiguration, NobleIDLOptions};
+
+use crate::{cargo_util::{load_options, CargoOptionsError}, emit::EmitError, CrateOptions, PackageMapping, RustPlugin};
+
+
+#[derive(From, Debug)]
+pub enum BuildScriptError {
+    IOError(std::io::Error),
+    OptionsError(CargoOptionsError),
+    CompileError(noble_idl_compiler::Error<EmitError>),
+}
+
+impl BuildScriptError {
+    // Cargo fails the build after the build script exits if it printed any errors.
+    pub fn report(self) {
+        match self {
+            BuildScriptError::IOError(e) => print_cargo_message("error", &e.to_string()),
+            BuildScriptError::OptionsError(e) => print_cargo_message("error", &e.to_string()),
+            BuildScriptError::CompileError(e) => {
+                for error in e.into_compile_errors() {
+                    print_cargo_message("error", &error.to_string());
+                }
+            },
+        }
+    }
+}
+
+
+/// Compiles the IDL files of a crate from its build script.
+///
+/// Files in the source directory of the crate and its dependencies are found using cargo metadata.
+/// Additional files and package mappings can be added before compiling.
+#[derive(Debug, Default)]
+pub struct BuildScript {
+    files: Vec<PathBuf>,
+    library_files: Vec<PathBuf>,
+    package_mapping: HashMap<String, String>,
+}
+
+impl BuildScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files.push(path.into());
+        self
+    }
+
+    pub fn library_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_files.push(path.into());
+        self
+    }
+
+    /// Maps a package to a module of the current crate, overriding the mapping from Cargo.toml.
+    pub fn package_mapping(mut self, package: impl Into<String>, module: impl Into<String>) -> Self {
+        self.package_mapping.insert(package.into(), module.into());
+        self
+    }
+
+    pub fn compile(self) -> Result<(), BuildScriptError> {
+        let cargo_options = load_options()?;
+        let rust_options = cargo_options.compiler_options;
+
+        let mut language_options = rust_options.language_options;
+
+        language_options.crates.crate_options
+            .entry(language_options.crate_name.clone())
+            .or_insert_with(|| CrateOptions {
+                package_mapping: PackageMapping {
+                    package_mapping: HashMap::new(),
+                },
+            })
+            .package_mapping.package_mapping
+            .extend(self.package_mapping);
+
+        let files = merge_files(rust_options.input_files, self.files)?;
+        let library_files = merge_files(rust_options.library_files, self.library_files)?;
+
+        // These are printed before compiling so that a failed build is retried once the inputs change.
+        for path in files.iter().chain(&library_files).chain(&cargo_options.watched_paths) {
+            println!("cargo::rerun-if-changed={}", path.display());
+        }
+
+        let options = NobleIDLOptions {
+            library_files,
+            files,
+            plugin_options: language_options,
+            lints: LintConfiguration::default(),
+        };
+
+        let result = compile(&RustPlugin, &options)?;
+
+        for diagnostic in &result.diagnostics {
+            let kind = match diagnostic.severity {
+                NobleIdlDiagnosticSeverity::Error => "error",
+                NobleIdlDiagnosticSeverity::Warning | NobleIdlDiagnosticSeverity::Note => "warning",
+            };
+
+            print_cargo_message(kind, &diagnostic.message);
+        }
+
+        Ok(())
+    }
+
+    /// Compiles and reports any error to cargo instead of returning it.
+    pub fn run(self) {
+        if let Err(e) = self.compile() {
+            e.report();
+        }
+    }
+}
+
+// Scanned files are canonical, so added files are canonicalized to avoid loading a file twice.
+fn merge_files(scanned: Vec<String>, added: Vec<PathBuf>) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut files = scanned.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+
+    for file in added {
+        let file = std::fs::canonicalize(file)?;
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+
+    Ok(files)
+}
+
+// Cargo only reads the first line of a message.
+fn print_cargo_message(kind: &str, message: &str) {
+    for line in message.lines() {
+        println!("cargo::{}={}", kind, line);
+    }
+}
@@ -1,40 +1,105 @@
-use std::{collections::{HashMap, HashSet, VecDeque}, ffi::OsStr, path::{Path, PathBuf}};
+use std::{collections::{HashMap, HashSet, VecDeque}, path::{Path, PathBuf}};
 
 use cargo_metadata::{CargoOpt, DependencyKind, MetadataCommand};
+use derive_more::From;
+use noble_idl_compiler::discovery::{discover_files, DiscoveryError, Excludes};
 
-use crate::{CrateOptions, Crates, PackageMapping, RustIDLCompilerOptions, RustLanguageOptions};
+use crate::{CrateOptions, Crates, OutputLayout, PackageMapping, RustIDLCompilerOptions, RustLanguageOptions};
 
 
 
 
+// Keys are kebab-case like the rest of Cargo.toml; snake_case spellings are also accepted.
 #[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
 struct NobleIDLRustMetadata {
+    #[serde(alias = "package_mapping")]
     package_mapping: HashMap<String, String>,
+
+    // Directories relative to the package manifest. Defaults to the directory of the lib target.
+    #[serde(alias = "source_dirs")]
+    source_dirs: Option<Vec<String>>,
+
+    // Globs relative to each source directory.
+    #[serde(default)]
+    include: Vec<String>,
+
+    #[serde(default)]
+    exclude: Vec<String>,
+
+    // The files of a library-only crate are never generated, even for the root crate.
+    #[serde(default, alias = "library_only")]
+    library_only: bool,
+}
+
+
+#[derive(From, Debug)]
+pub enum CargoOptionsError {
+    IOError(std::io::Error),
+    MetadataError(cargo_metadata::Error),
+    DiscoveryError(DiscoveryError),
+
+    #[from(ignore)]
+    MissingEnvironmentVariable(&'static str),
+
+    MissingResolve,
+    MissingRootCrate,
+
+    #[from(ignore)]
+    MissingPackage(String),
+
+    #[from(ignore)]
+    InvalidMetadata(String, serde_json::Error),
+
+    #[from(ignore)]
+    InvalidPath(PathBuf),
+}
+
+impl std::fmt::Display for CargoOptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CargoOptionsError::IOError(e) => write!(f, "{}", e),
+            CargoOptionsError::MetadataError(e) => write!(f, "Could not load cargo metadata: {}", e),
+            CargoOptionsError::DiscoveryError(e) => write!(f, "Could not scan for IDL files: {:?}", e),
+            CargoOptionsError::MissingEnvironmentVariable(name) => write!(f, "Environment variable {} is not set", name),
+            CargoOptionsError::MissingResolve => write!(f, "Cargo metadata does not contain a dependency resolution"),
+            CargoOptionsError::MissingRootCrate => write!(f, "Cargo metadata does not contain a root crate"),
+            CargoOptionsError::MissingPackage(id) => write!(f, "Cargo metadata does not contain package {}", id),
+            CargoOptionsError::InvalidMetadata(package, e) => write!(f, "Invalid noble-idl metadata in package {}: {}", package, e),
+            CargoOptionsError::InvalidPath(path) => write!(f, "Path is not valid UTF-8: {}", path.display()),
+        }
+    }
 }
 
 
+pub struct CargoOptions {
+    pub compiler_options: RustIDLCompilerOptions,
 
+    // Files and directories that determine the inputs, for rerun-if-changed.
+    pub watched_paths: Vec<PathBuf>,
+}
 
 
-pub fn load_options() -> RustIDLCompilerOptions {
-    let mut manifest_path: PathBuf = std::env::var_os("CARGO_MANIFEST_DIR")
-        .map(PathBuf::from)
-        .unwrap_or_else(|| std::env::current_dir().unwrap());
+pub fn load_options() -> Result<CargoOptions, CargoOptionsError> {
+    let mut manifest_path: PathBuf = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::current_dir()?,
+    };
     manifest_path.push("Cargo.toml");
 
     let metadata = MetadataCommand::new()
-        .manifest_path(manifest_path)
+        .manifest_path(&manifest_path)
         .features(CargoOpt::AllFeatures)
-        .exec()
-        .unwrap();
+        .exec()?;
 
     let packages = &metadata.packages;
-    let resolve = metadata.resolve.as_ref().expect("Failed to get dependency resolution");
+    let resolve = metadata.resolve.as_ref().ok_or(CargoOptionsError::MissingResolve)?;
     let resolve_nodes = &resolve.nodes;
 
 
-    let mut input_dirs = HashSet::new();
-    let mut library_dirs = HashSet::new();
+    let mut input_files = Vec::new();
+    let mut library_files = Vec::new();
+    let mut source_dirs = Vec::new();
 
     let mut root_crate_name = None;
     let mut crates = HashMap::new();
@@ -43,9 +108,19 @@ pub fn load_options() -> RustIDLCompilerOptions {
     let mut to_scan = VecDeque::new();
     let mut visited_packages = HashSet::new();
 
-    let root_crate = resolve.root.as_ref().expect("Could not get resolve.root.");
+    let root_crate = resolve.root.as_ref().ok_or(CargoOptionsError::MissingRootCrate)?;
     to_scan.push_back(root_crate);
 
+    // Generated code refers to direct dependencies by the name they have in the root crate, which may be renamed.
+    let dependency_names = resolve_nodes
+        .iter()
+        .find(|node| node.id == *root_crate)
+        .ok_or_else(|| CargoOptionsError::MissingPackage(root_crate.to_string()))?
+        .deps
+        .iter()
+        .map(|dep| (&dep.pkg, dep.name.as_str()))
+        .collect::<HashMap<_, _>>();
+
     while let Some(current_id) = to_scan.pop_front() {
         if visited_packages.contains(&current_id) {
             continue;
@@ -58,7 +133,7 @@ pub fn load_options() -> RustIDLCompilerOptions {
         let resolve_node = resolve_nodes
             .iter()
             .find(|node| node.id == *current_id)
-            .expect(&format!("Failed to get resolve node {}", current_id));
+            .ok_or_else(|| CargoOptionsError::MissingPackage(current_id.to_string()))?;
 
         for dep in &resolve_node.deps {
             if !dep.dep_kinds.iter().any(|kind| kind.kind == DependencyKind::Normal) {
@@ -71,7 +146,7 @@ pub fn load_options() -> RustIDLCompilerOptions {
         let package = packages
             .iter()
             .find(|pkg| pkg.id == *current_id)
-            .expect(&format!("Failed to get package {}", current_id));
+            .ok_or_else(|| CargoOptionsError::MissingPackage(current_id.to_string()))?;
 
         let package_name = package.name.as_str();
 
@@ -79,73 +154,110 @@ pub fn load_options() -> RustIDLCompilerOptions {
             root_crate_name = Some(package_name);
         }
 
-        if let Some(noble_idl_packages) = package.metadata.get("noble-idl")
-            .and_then(|noble_idl| serde_json::from_value::<NobleIDLRustMetadata>(noble_idl.clone()).ok())
-        {
+        if let Some(noble_idl) = package.metadata.get("noble-idl") {
+            let noble_idl_metadata = serde_json::from_value::<NobleIDLRustMetadata>(noble_idl.clone())
+                .map_err(|e| CargoOptionsError::InvalidMetadata(package_name.to_owned(), e))?;
+
+            let package_dirs = get_source_dirs(package, &noble_idl_metadata)?;
+            let files = scan_dirs(&package_dirs, &noble_idl_metadata)?;
+
+            if is_root_crate && !noble_idl_metadata.library_only {
+                input_files.extend(files);
+            }
+            else {
+                library_files.extend(files);
+            }
+
+            source_dirs.extend(package_dirs);
+
             let crate_options = CrateOptions {
                 package_mapping: PackageMapping {
-                    package_mapping: noble_idl_packages.package_mapping,
+                    package_mapping: noble_idl_metadata.package_mapping,
                 },
             };
 
-            crates.insert(package_name.to_owned(), crate_options);
-
-
-
-            add_idl_files(if is_root_crate { &mut input_dirs } else { &mut library_dirs }, package);
+            let crate_name = dependency_names.get(current_id).copied().unwrap_or(package_name);
+            crates.insert(crate_name.to_owned(), crate_options);
         }
     }
 
-    let input_files = scan_dirs(&input_dirs);
-    let library_files = scan_dirs(&library_dirs);
-
-    let mut output_dir = PathBuf::from(std::env::var_os("OUT_DIR").expect("OUT_DIR not specified"));
+    let mut output_dir = PathBuf::from(std::env::var_os("OUT_DIR").ok_or(CargoOptionsError::MissingEnvironmentVariable("OUT_DIR"))?);
     output_dir.push("noble_idl");
 
-    RustIDLCompilerOptions {
-        language_options: RustLanguageOptions {
-            crate_name: root_crate_name.expect("Root crate name was not found").to_owned(),
-            crates: Crates {
-                crate_options: crates,
+    let mut watched_paths = vec![ manifest_path ];
+    watched_paths.extend(source_dirs);
+    watched_paths.sort();
+    watched_paths.dedup();
+
+    Ok(CargoOptions {
+        compiler_options: RustIDLCompilerOptions {
+            language_options: RustLanguageOptions {
+                crate_name: root_crate_name.ok_or(CargoOptionsError::MissingRootCrate)?.to_owned(),
+                crates: Crates {
+                    crate_options: crates,
+                },
+
+                output_dir: path_to_string(output_dir)?,
+                output_layout: OutputLayout::Flat,
             },
 
-            output_dir: output_dir.into_os_string().into_string().unwrap(),
+            input_files,
+            library_files,
         },
 
-        input_files,
-        library_files,
-    }
+        watched_paths,
+    })
 }
 
-fn add_idl_files<'a>(dirs: &mut HashSet<PathBuf>, package: &cargo_metadata::Package) {
-    let Some(lib) = package.targets.iter().find(|target| target.is_lib()) else { return; };
+fn get_source_dirs(package: &cargo_metadata::Package, metadata: &NobleIDLRustMetadata) -> Result<Vec<PathBuf>, CargoOptionsError> {
+    let dirs = match &metadata.source_dirs {
+        Some(source_dirs) => {
+            let mut package_dir = PathBuf::from(&package.manifest_path);
+            package_dir.pop();
+
+            source_dirs.iter().map(|dir| package_dir.join(dir)).collect()
+        },
+
+        None => {
+            let Some(lib) = package.targets.iter().find(|target| target.is_lib()) else { return Ok(Vec::new()); };
 
-    let path = PathBuf::from(&lib.src_path);
-    let mut path = std::fs::canonicalize(path).expect("Could not canonicalize path.");
-    path.pop();
+            let mut path = PathBuf::from(&lib.src_path);
+            path.pop();
 
-    dirs.insert(path);
+            vec![ path ]
+        },
+    };
 
+    dirs.into_iter()
+        .map(|dir| Ok(std::fs::canonicalize(dir)?))
+        .collect()
 }
 
-fn scan_dirs(dirs: &HashSet<PathBuf>) -> Vec<String> {
+fn scan_dirs(dirs: &[PathBuf], metadata: &NobleIDLRustMetadata) -> Result<Vec<String>, CargoOptionsError> {
     let mut files = Vec::new();
-    for dir in dirs {
-        scan_dir(&mut files, dir)
-    }
-    files
-}
 
-fn scan_dir(files: &mut Vec<String>, dir: &Path) {
-    for f in std::fs::read_dir(dir).unwrap() {
-        let f = f.unwrap();
+    for dir in dirs {
+        let excludes = Excludes::new(dir, &metadata.exclude)?;
 
-        if f.metadata().unwrap().is_dir() {
-            scan_dir(files, &f.path());
-        }
-        else if f.path().extension() == Some(OsStr::new("nidl")) {
-            files.push(f.path().to_str().unwrap().to_owned());
+        let dir_files = if metadata.include.is_empty() {
+            discover_files(Path::new(""), &[dir], &excludes)?
         }
+        else {
+            discover_files(dir, &metadata.include, &excludes)?
+        };
+
+        files.extend(dir_files);
     }
+
+    files.sort();
+    files.dedup();
+
+    files.into_iter()
+        .map(path_to_string)
+        .collect()
 }
 
+fn path_to_string(path: PathBuf) -> Result<String, CargoOptionsError> {
+    path.into_os_string().into_string()
+        .map_err(|path| CargoOptionsError::InvalidPath(PathBuf::from(path)))
+}
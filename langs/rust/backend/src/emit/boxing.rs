@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+
+use esexpr::ESExprCodec;
+use noble_idl_api::*;
+
+
+#[derive(Clone, Copy)]
+pub(super) enum TypeBoxing<'b> {
+	None,
+	All,
+	Recursive(&'b HashSet<&'b QualifiedName>),
+}
+
+impl <'b> TypeBoxing<'b> {
+	pub fn is_boxed(&self, dfn: &DefinitionInfo) -> bool {
+		is_value_type(dfn) &&
+			match self {
+				TypeBoxing::None => false,
+				TypeBoxing::All => true,
+				TypeBoxing::Recursive(types) => types.contains(dfn.name.as_ref()),
+			}
+	}
+}
+
+// The boxing of the types nested in a value, which only carries over when everything is boxed.
+pub(super) fn inherited_boxing(boxing: TypeBoxing<'_>) -> TypeBoxing<'_> {
+	match boxing {
+		TypeBoxing::All => TypeBoxing::All,
+		TypeBoxing::None | TypeBoxing::Recursive(_) => TypeBoxing::None,
+	}
+}
+
+pub(super) fn is_value_type(dfn: &DefinitionInfo) -> bool {
+	match dfn.definition.as_ref() {
+		Definition::Record(_) | Definition::Enum(_) | Definition::ExceptionType(_) => true,
+		Definition::SimpleEnum(_) | Definition::ExternType(_) | Definition::Interface(_) => false,
+	}
+}
+
+// Arguments for a boxed-usage type parameter are stored behind a pointer already, so they never need boxing to break a cycle.
+pub(super) fn type_argument_boxing<'b>(dfn: Option<&DefinitionInfo>, index: usize, boxing: TypeBoxing<'b>) -> TypeBoxing<'b> {
+	match boxing {
+		TypeBoxing::Recursive(_) if dfn.is_some_and(|dfn| is_boxed_usage(dfn, index)) => TypeBoxing::None,
+		_ => boxing,
+	}
+}
+
+fn is_boxed_usage(dfn: &DefinitionInfo, index: usize) -> bool {
+	dfn.type_parameters.get(index)
+		.is_some_and(|tp| tp.annotations().iter()
+			.filter(|ann| ann.scope == "rust")
+			.filter_map(|ann| RustAnnTypeParameter::decode_esexpr(ann.value.clone()).ok())
+			.any(|ann| match ann {
+				RustAnnTypeParameter::BoxedUsage => true,
+			})
+		)
+}
+
+pub(super) fn get_field_boxing_override(field: &RecordField) -> Option<RustAnnRecordField> {
+	field.annotations.iter()
+		.filter(|ann| ann.scope == "rust")
+		.find_map(|ann| RustAnnRecordField::decode_esexpr(ann.value.clone()).ok())
+}
+
+
+// Maps each definition that is part of a cycle through by-value positions to the definitions in its cycle.
+// References to those definitions from within the cycle are boxed.
+pub(super) fn find_recursive_types<'a>(definition_map: &HashMap<&'a QualifiedName, &'a DefinitionInfo>) -> HashMap<&'a QualifiedName, HashSet<&'a QualifiedName>> {
+	let mut graph = HashMap::new();
+	for (name, dfn) in definition_map {
+		let mut refs = Vec::new();
+		for t in by_value_field_types(dfn) {
+			collect_value_references(definition_map, t, &mut refs);
+		}
+		graph.insert(*name, refs);
+	}
+
+	let mut tarjan = Tarjan {
+		graph: &graph,
+		index: 0,
+		indexes: HashMap::new(),
+		low_links: HashMap::new(),
+		stack: Vec::new(),
+		on_stack: HashSet::new(),
+		components: Vec::new(),
+	};
+
+	for &name in graph.keys() {
+		if !tarjan.indexes.contains_key(name) {
+			tarjan.visit(name);
+		}
+	}
+
+	let mut recursive_types = HashMap::new();
+	for component in tarjan.components {
+		let is_cycle = match component.as_slice() {
+			[name] => graph.get(name).is_some_and(|refs| refs.contains(name)),
+			_ => true,
+		};
+
+		if !is_cycle {
+			continue;
+		}
+
+		let members = component.iter().copied().collect::<HashSet<_>>();
+		for name in component {
+			recursive_types.insert(name, members.clone());
+		}
+	}
+
+	recursive_types
+}
+
+fn by_value_field_types(dfn: &DefinitionInfo) -> Vec<&TypeExpr> {
+	match dfn.definition.as_ref() {
+		Definition::Record(r) => by_value_types_of_fields(&r.fields).collect(),
+		Definition::Enum(e) => e.cases.iter().flat_map(|c| by_value_types_of_fields(&c.fields)).collect(),
+		Definition::ExceptionType(ex) => vec![&ex.information],
+		Definition::SimpleEnum(_) | Definition::ExternType(_) | Definition::Interface(_) => vec![],
+	}
+}
+
+// Fields that are always boxed do not form a cycle.
+fn by_value_types_of_fields(fields: &[Box<RecordField>]) -> impl Iterator<Item = &TypeExpr> {
+	fields.iter()
+		.filter(|f| !matches!(get_field_boxing_override(f), Some(RustAnnRecordField::Boxed)))
+		.map(|f| f.field_type.as_ref())
+}
+
+fn collect_value_references<'a>(definition_map: &HashMap<&'a QualifiedName, &'a DefinitionInfo>, t: &TypeExpr, refs: &mut Vec<&'a QualifiedName>) {
+	let TypeExpr::DefinedType(name, args) = t else { return; };
+	let Some(dfn) = definition_map.get(name.as_ref()).copied() else { return; };
+
+	if is_value_type(dfn) {
+		refs.push(&dfn.name);
+	}
+
+	for (index, arg) in args.iter().enumerate() {
+		if !is_boxed_usage(dfn, index) {
+			collect_value_references(definition_map, arg, refs);
+		}
+	}
+}
+
+struct Tarjan<'g, 'a> {
+	graph: &'g HashMap<&'a QualifiedName, Vec<&'a QualifiedName>>,
+	index: usize,
+	indexes: HashMap<&'a QualifiedName, usize>,
+	low_links: HashMap<&'a QualifiedName, usize>,
+	stack: Vec<&'a QualifiedName>,
+	on_stack: HashSet<&'a QualifiedName>,
+	components: Vec<Vec<&'a QualifiedName>>,
+}
+
+impl <'g, 'a> Tarjan<'g, 'a> {
+	fn visit(&mut self, name: &'a QualifiedName) {
+		self.indexes.insert(name, self.index);
+		self.low_links.insert(name, self.index);
+		self.index += 1;
+		self.stack.push(name);
+		self.on_stack.insert(name);
+
+		for &next in self.graph.get(name).into_iter().flatten() {
+			if !self.indexes.contains_key(next) {
+				self.visit(next);
+				let low_link = self.low_links[name].min(self.low_links[next]);
+				self.low_links.insert(name, low_link);
+			}
+			else if self.on_stack.contains(next) {
+				let low_link = self.low_links[name].min(self.indexes[next]);
+				self.low_links.insert(name, low_link);
+			}
+		}
+
+		if self.low_links[name] == self.indexes[name] {
+			let mut component = Vec::new();
+			while let Some(member) = self.stack.pop() {
+				self.on_stack.remove(member);
+				component.push(member);
+				if member == name {
+					break;
+				}
+			}
+			self.components.push(component);
+		}
+	}
+}
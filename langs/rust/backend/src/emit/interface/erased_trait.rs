@@ -163,7 +163,7 @@ impl <'a> TraitEmitter<'a> for InterfaceTraitErasedEmitter<'a> {
 		"_Erased"
 	}
 
-	fn trait_vis(&self) -> syn::Visibility {
+	fn trait_vis(&self, _dfn: &DefinitionInfo) -> syn::Visibility {
 		syn::Visibility::Inherited
 	}
 
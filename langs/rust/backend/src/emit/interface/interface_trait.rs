@@ -3,7 +3,7 @@ use super::super::type_emitter::*;
 use super::super::trait_emitter::*;
 use super::super::method_emitter::*;
 
-use super::super::EmitError;
+use super::super::{definition_visibility, EmitError};
 
 use syn::parse_quote;
 use noble_idl_api::*;
@@ -19,8 +19,8 @@ impl <'a> TraitEmitter<'a> for InterfaceTraitInterfaceEmitter<'a> {
 		"_Interface"
 	}
 
-	fn trait_vis(&self) -> syn::Visibility {
-		syn::Visibility::Public(Default::default())
+	fn trait_vis(&self, dfn: &DefinitionInfo) -> syn::Visibility {
+		definition_visibility(dfn)
 	}
 
 	fn trait_type_emitter(&self) -> &Self::TraitTE {
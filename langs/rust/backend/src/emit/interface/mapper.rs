@@ -1,7 +1,7 @@
 use noble_idl_api::*;
 use syn::parse_quote;
 use syn::punctuated::Punctuated;
-use crate::emit::{ModEmitter, EmitError, dfn_as_type};
+use crate::emit::{ModEmitter, EmitError, definition_visibility, dfn_as_type};
 use crate::emit::ident::*;
 use crate::emit::type_emitter::*;
 use crate::emit::method_emitter::*;
@@ -192,7 +192,7 @@ impl <'a> InterfaceMapperEmitter<'a> {
 					parse_quote! { #[derive(::std::clone::Clone, ::std::marker::Copy)] },
 					parse_quote! { #[allow(non_camel_case_types)] },
 				],
-				vis: syn::Visibility::Public(Default::default()),
+				vis: definition_visibility(dfn),
 				struct_token: Default::default(),
 				ident: mapped_name.clone(),
 				generics: type_parameters.clone(),
@@ -2,7 +2,7 @@ use noble_idl_api::*;
 use quote::format_ident;
 use syn::parse_quote;
 
-use crate::emit::{EmitError, dfn_as_type};
+use crate::emit::{EmitError, deprecated_attr, dfn_as_type};
 use crate::emit::ident::*;
 use crate::emit::type_emitter::*;
 use crate::emit::method_emitter::*;
@@ -73,6 +73,11 @@ impl <'a> MethodEmitter<'a> for InterfacePublicImplEmitter<'a> {
 		syn::Visibility::Public(Default::default())
 	}
 
+	fn method_attrs(&self, m: &InterfaceMethod) -> Vec<syn::Attribute> {
+		let deprecated = deprecated_attr(m.deprecated.as_deref());
+		parse_quote! { #deprecated }
+	}
+
 	fn emit_method_body<'b: 'a>(&self, m: &'b InterfaceMethod) -> Result<Option<syn::Block>, EmitError> {
 		let method_name = convert_id_snake(&m.name);
 
@@ -13,6 +13,9 @@ pub(super) trait MethodEmitter<'a> {
 	fn method_vis(&self) -> syn::Visibility {
 		syn::Visibility::Inherited
 	}
+	fn method_attrs(&self, _m: &InterfaceMethod) -> Vec<syn::Attribute> {
+		vec![]
+	}
 
 
 	fn emit_trait_method<'b: 'a>(&self, m: &'b InterfaceMethod) -> Result<syn::TraitItemFn, EmitError> {
@@ -50,7 +53,7 @@ pub(super) trait MethodEmitter<'a> {
 		let body = self.emit_method_body(m)?;
 
 		Ok(syn::ImplItemFn {
-			attrs: vec![],
+			attrs: self.method_attrs(m),
 			sig: syn::Signature {
 				constness: None,
 				asyncness: None,
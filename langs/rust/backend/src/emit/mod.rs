@@ -1,13 +1,14 @@
-use std::{collections::HashMap, io::{Read, Write}, path::PathBuf, vec};
+use std::{collections::{BTreeMap, BTreeSet, HashMap, HashSet}, io::{Read, Write}, path::{Path, PathBuf}, vec};
 use num_bigint::{BigInt, BigUint, Sign};
 use proc_macro2::TokenStream;
 use quote::{quote, format_ident, ToTokens};
 
 use esexpr::ESExprCodec;
 use noble_idl_api::*;
+use noble_idl_api::annotation_schema::{AnnotationLocation, AnnotationProblem, AnnotationRegistry};
 use syn::{parse_quote, punctuated::Punctuated};
 
-use crate::{annotations::{RustAnnEnum, RustAnnEnumCase, RustAnnRecord}, RustLanguageOptions};
+use crate::{annotations::check_rust_annotation, OutputLayout, RustLanguageOptions};
 
 
 mod ident;
@@ -25,6 +26,12 @@ mod type_mapper;
 
 mod interface;
 
+mod boxing;
+use boxing::*;
+
+mod serde;
+use serde::*;
+
 #[derive(derive_more::From, Debug)]
 pub enum EmitError {
 	#[from(ignore)]
@@ -34,6 +41,7 @@ pub enum EmitError {
 	ProtocolParseError(esexpr_binary::ParseError),
 	ProtocolDecodeError(esexpr::DecodeError),
 	ProtocolGenerateError(esexpr_binary::GeneratorError),
+	ProtocolError(ProtocolError),
 	RustParseError(syn::Error),
 	InvalidLiteralForType(TypeExpr),
 	InvalidFileName,
@@ -43,37 +51,54 @@ pub enum EmitError {
 
 	#[from(ignore)]
 	UnitWithFields(QualifiedName, Option<String>),
-}
 
-pub fn emit(request: NobleIdlGenerationRequest<RustLanguageOptions>) -> Result<NobleIdlGenerationResult, EmitError> {
-	let pkg_mapping = get_package_mapping(&request.language_options);
+	#[from(ignore)]
+	InvalidAnnotation(AnnotationLocation, String),
+
+	#[from(ignore)]
+	UnknownMappedTypeParameter(QualifiedName, String),
 
-	let definition_map = request.model.definitions
-		.iter()
-		.map(|dfn| (&*dfn.name, &**dfn))
-		.collect::<HashMap<_, _>>();
+	#[from(ignore)]
+	ModuleOutsideRoot(String),
 
-	let mut emitter = ModEmitter {
-		definitions: &request.model.definitions,
-		pkg_mapping,
-		current_crate: &request.language_options.crate_name,
+	EmptyRootModule,
 
-		output_dir: PathBuf::from(&request.language_options.output_dir),
-		output_files: Vec::new(),
+	#[from(ignore)]
+	UnknownDefaultCase(QualifiedName, String),
 
-		definition_map,
-	};
+	#[from(ignore)]
+	DuplicateBuilderName(QualifiedName, String),
+}
+
+pub fn emit(request: NobleIdlGenerationRequest<RustLanguageOptions>) -> Result<NobleIdlGenerationResult, EmitError> {
+	check_annotations(&request.model)?;
+
+	let output_dir = PathBuf::from(request.output_dir.as_ref().unwrap_or(&request.language_options.output_dir));
+	let mut emitter = ModEmitter::new(&request.model, &request.language_options, output_dir);
 
 	emitter.emit_modules()?;
 
 	Ok(emitter.generation_result())
 }
 
+// Emits the code for all modules as nested inline modules rooted at the crate root instead of writing files.
+pub fn emit_inline(model: &NobleIdlModel, options: &RustLanguageOptions) -> Result<TokenStream, EmitError> {
+	check_annotations(model)?;
+
+	let mut emitter = ModEmitter::new(model, options, PathBuf::new());
+
+	let module_groups = emitter.group_definitions()?;
+	let tree = emitter.build_module_tree(module_groups);
+
+	emitter.emit_inline_module(&tree, &[])
+}
+
 pub fn emit_from_stream<R: Read, W: Write>(input: R, mut output: W) -> Result<(), EmitError> {
 	let mut gen = esexpr_binary::ExprGenerator::new(&mut output);
 
 	for def in esexpr_binary::parse(input) {
 		let def = def?;
+		check_protocol_version(&def)?;
 		let def = ESExprCodec::decode_esexpr(def)?;
 
 		let res = emit(def)?;
@@ -95,87 +120,331 @@ struct RustModule<'a> {
 	module: &'a str,
 }
 
+#[derive(Default)]
+struct ModuleNode<'a> {
+	definitions: Vec<&'a DefinitionInfo>,
+	submodules: BTreeSet<String>,
+}
+
+type ModuleTree<'a> = BTreeMap<Vec<String>, ModuleNode<'a>>;
+
+struct ModuleFile<'a> {
+	path: PathBuf,
+	definitions: Vec<&'a DefinitionInfo>,
+	submodules: Vec<String>,
+}
+
 struct ModEmitter<'a> {
 	definitions: &'a Vec<Box<DefinitionInfo>>,
 	pkg_mapping: HashMap<PackageName, RustModule<'a>>,
 	current_crate: &'a str,
 
 	output_dir: PathBuf,
+	output_layout: &'a OutputLayout,
 	output_files: Vec<String>,
 
 	definition_map: HashMap<&'a QualifiedName, &'a DefinitionInfo>,
+	recursive_types: HashMap<&'a QualifiedName, HashSet<&'a QualifiedName>>,
+	package_derives: HashMap<&'a PackageName, Vec<String>>,
+	boxed_packages: HashSet<&'a PackageName>,
+	serde_packages: HashSet<&'a PackageName>,
+	builder_packages: HashSet<&'a PackageName>,
 }
 
 impl <'a> ModEmitter<'a> {
+	fn new(model: &'a NobleIdlModel, options: &'a RustLanguageOptions, output_dir: PathBuf) -> Self {
+		let definition_map = model.definitions
+			.iter()
+			.map(|dfn| (&*dfn.name, &**dfn))
+			.collect::<HashMap<_, _>>();
+
+		let recursive_types = find_recursive_types(&definition_map);
+
+		let package_derives = model.packages
+			.iter()
+			.map(|pkg| (&*pkg.name, get_package_derives(pkg)))
+			.collect::<HashMap<_, _>>();
+
+		let boxed_packages = model.packages
+			.iter()
+			.filter(|pkg| has_package_boxing(pkg))
+			.map(|pkg| &*pkg.name)
+			.collect::<HashSet<_>>();
+
+		let serde_packages = model.packages
+			.iter()
+			.filter(|pkg| has_package_serde(pkg))
+			.map(|pkg| &*pkg.name)
+			.collect::<HashSet<_>>();
+
+		let builder_packages = model.packages
+			.iter()
+			.filter(|pkg| has_package_builder(pkg))
+			.map(|pkg| &*pkg.name)
+			.collect::<HashSet<_>>();
+
+		ModEmitter {
+			definitions: &model.definitions,
+			pkg_mapping: get_package_mapping(options),
+			current_crate: &options.crate_name,
+
+			output_dir,
+			output_layout: &options.output_layout,
+			output_files: Vec::new(),
+
+			definition_map,
+			recursive_types,
+			package_derives,
+			boxed_packages,
+			serde_packages,
+			builder_packages,
+		}
+	}
+
 	fn emit_modules(&mut self) -> Result<(), EmitError> {
-		let mut package_groups = HashMap::new();
+		let module_groups = self.group_definitions()?;
+
+		let modules = match &self.output_layout {
+			OutputLayout::Flat => module_groups.into_iter()
+				.map(|(module, definitions)| ModuleFile {
+					path: self.build_flat_module_path(&module),
+					definitions,
+					submodules: Vec::new(),
+				})
+				.collect::<Vec<_>>(),
+
+			OutputLayout::ModuleTree { root_module } => {
+				if root_module.is_empty() {
+					return Err(EmitError::EmptyRootModule);
+				}
+
+				let mut relative_groups = Vec::new();
+				for (module, dfns) in module_groups {
+					let relative_module = if module == *root_module {
+						""
+					}
+					else {
+						module.strip_prefix(root_module.as_str())
+							.and_then(|m| m.strip_prefix("::"))
+							.ok_or_else(|| EmitError::ModuleOutsideRoot(module.clone()))?
+					};
+
+					relative_groups.push((relative_module.to_owned(), dfns));
+				}
+
+				let tree = self.build_module_tree(relative_groups);
+				self.build_module_tree_files(tree)
+			},
+		};
+
+		let mut file_names = BTreeSet::new();
+		for module in modules {
+			let p = self.emit_module(module)?;
+			file_names.insert(self.get_relative_file_name(&p)?);
+			self.output_files.push(p.as_os_str().to_str().ok_or(EmitError::InvalidFileName)?.to_owned());
+		}
+
+		self.update_manifest(&file_names)?;
+
+		Ok(())
+	}
+
+	// Modules are emitted in a fixed order so that repeated runs produce the same output.
+	fn group_definitions(&self) -> Result<BTreeMap<String, Vec<&'a DefinitionInfo>>, EmitError> {
+		let mut module_groups = BTreeMap::new();
 		for dfn in self.definitions {
 			if dfn.is_library {
 				continue;
 			}
 
-			let dfns = package_groups.entry(dfn.name.package_name())
+			let module = self.get_rust_module(dfn.name.package_name())?.module;
+
+			let dfns = module_groups.entry(module.to_owned())
 				.or_insert_with(|| Vec::new());
 
 			dfns.push(&**dfn);
 		}
 
-		for (pkg, dfns) in package_groups {
-			let p = self.emit_module(pkg, &dfns)?;
-			self.output_files.push(p.as_os_str().to_str().ok_or(EmitError::InvalidFileName)?.to_owned());
+		Ok(module_groups)
+	}
+
+	// Every ancestor of a generated module is part of the tree so that it can declare its submodules, even if it has no definitions.
+	// Module paths are relative to the root of the tree.
+	fn build_module_tree(&self, module_groups: impl IntoIterator<Item = (String, Vec<&'a DefinitionInfo>)>) -> ModuleTree<'a> {
+		let mut tree = ModuleTree::new();
+		tree.entry(Vec::new()).or_default();
+
+		for (module, dfns) in module_groups {
+			let segments = module.split("::")
+				.filter(|segment| !segment.is_empty())
+				.map(str::to_owned)
+				.collect::<Vec<_>>();
+
+			for i in 0..segments.len() {
+				tree.entry(segments[..i].to_vec()).or_default().submodules.insert(segments[i].clone());
+			}
+
+			tree.entry(segments).or_default().definitions.extend(dfns);
 		}
 
-		Ok(())
+		tree
 	}
 
-	fn generation_result(self) -> NobleIdlGenerationResult {
-		NobleIdlGenerationResult {
-			generated_files: self.output_files,
+	fn build_module_tree_files(&self, tree: ModuleTree<'a>) -> Vec<ModuleFile<'a>> {
+		tree.into_iter()
+			.map(|(segments, ModuleNode { definitions, submodules })| {
+				let mut p = self.output_dir.clone();
+				match segments.split_last() {
+					Some((last, parents)) => {
+						p.extend(parents);
+						p.push(format!("{}.rs", last));
+					},
+
+					None => p.push("mod.rs"),
+				}
+
+				ModuleFile {
+					path: p,
+					definitions,
+					submodules: submodules.into_iter().collect(),
+				}
+			})
+			.collect()
+	}
+
+	fn emit_inline_module(&mut self, tree: &ModuleTree<'a>, segments: &[String]) -> Result<TokenStream, EmitError> {
+		let Some(node) = tree.get(segments) else { return Ok(TokenStream::new()); };
+
+		let mut code = TokenStream::new();
+		for submodule in &node.submodules {
+			let mut sub_segments = segments.to_vec();
+			sub_segments.push(submodule.clone());
+
+			let name = idstr(submodule);
+			let sub_code = self.emit_inline_module(tree, &sub_segments)?;
+
+			code.extend(quote! {
+				pub mod #name {
+					#sub_code
+				}
+			});
 		}
+
+		self.check_builder_names(&node.definitions)?;
+
+		for dfn in &node.definitions {
+			code.extend(self.emit_definition(dfn)?);
+		}
+
+		Ok(code)
+	}
+
+	fn get_relative_file_name(&self, p: &Path) -> Result<String, EmitError> {
+		let relative = p.strip_prefix(&self.output_dir).map_err(|_| EmitError::InvalidFileName)?;
+
+		Ok(
+			relative.components()
+				.map(|c| c.as_os_str().to_str().ok_or(EmitError::InvalidFileName))
+				.collect::<Result<Vec<_>, _>>()?
+				.join("/")
+		)
 	}
 
-	fn build_package_path(&self, package_name: &PackageName) -> Result<PathBuf, EmitError> {
+	// The manifest lists the files generated for the current crate by the previous run.
+	// Files that are no longer generated are removed from the output directory.
+	fn update_manifest(&self, file_names: &BTreeSet<String>) -> Result<(), EmitError> {
+		let mut manifest_path = self.output_dir.clone();
+		manifest_path.push(format!("{}.noble-idl-manifest", self.current_crate.replace("-", "_")));
+
+		let previous = match std::fs::read_to_string(&manifest_path) {
+			Ok(previous) => previous,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+			Err(e) => return Err(e.into()),
+		};
+
+		for name in previous.lines() {
+			// Only relative paths are removed, so a modified manifest cannot delete files outside the output directory.
+			if name.is_empty() || file_names.contains(name) || !Path::new(name).components().all(|c| matches!(c, std::path::Component::Normal(_))) {
+				continue;
+			}
+
+			let mut p = self.output_dir.clone();
+			p.extend(name.split("/"));
+
+			match std::fs::remove_file(&p) {
+				Ok(()) => {},
+				Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+				Err(e) => return Err(e.into()),
+			}
+
+			// Directories of removed modules are cleaned up once they are empty.
+			let mut dir = p.parent();
+			while let Some(d) = dir.filter(|d| *d != self.output_dir) {
+				if std::fs::remove_dir(d).is_err() {
+					break;
+				}
+				dir = d.parent();
+			}
+		}
+
+		// No modules are written for library-only crates, so the output directory may not exist yet.
+		std::fs::create_dir_all(&self.output_dir)?;
+
+		let manifest = file_names.iter().map(|name| format!("{}\n", name)).collect::<String>();
+		write_if_changed(&manifest_path, &manifest)?;
 
+		Ok(())
+	}
+
+	fn generation_result(self) -> NobleIdlGenerationResult {
+		let mut result = NobleIdlGenerationResult::new(self.output_files);
+		result.capabilities.push(NobleIdlPluginCapability::MultipleRequests);
+		result
+	}
 
+	fn build_flat_module_path(&self, module: &str) -> PathBuf {
 		let mut p = self.output_dir.clone();
 
 		let crate_name = self.current_crate.replace("-", "_");
 
-		let rust_module = self.get_rust_module(package_name)?;
-		if rust_module.module.is_empty() {
+		if module.is_empty() {
 			p.push(format!("{}.rs", crate_name));
 		}
 		else {
-			p.push(format!("{}::{}.rs", crate_name, rust_module.module));
+			p.push(format!("{}::{}.rs", crate_name, module));
 		}
 
-		Ok(p)
+		p
 	}
 
 	fn get_rust_module(&self, package_name: &PackageName) -> Result<&RustModule, EmitError> {
 		self.pkg_mapping.get(package_name).ok_or_else(|| EmitError::UnmappedPackage(package_name.clone()))
 	}
 
-	fn emit_module(&mut self, package_name: &PackageName, definitions: &[&'a DefinitionInfo]) -> Result<PathBuf, EmitError>  {
-		let p = self.build_package_path(package_name)?;
+	fn emit_module(&mut self, module: ModuleFile<'a>) -> Result<PathBuf, EmitError>  {
+		let p = module.path;
 
 		if let Some(parent) = p.parent() {
 			std::fs::create_dir_all(parent)?;
 		}
 
-		let mut f = std::fs::File::create(&p)?;
+		self.check_builder_names(&module.definitions)?;
 
-		let defs_code = definitions.iter().map(|dfn| self.emit_definition(dfn)).collect::<Result<TokenStream, _>>()?;
+		let submodules = module.submodules.iter().map(|m| idstr(m));
+		let defs_code = module.definitions.iter().map(|dfn| self.emit_definition(dfn)).collect::<Result<TokenStream, _>>()?;
 
-		match syn::parse2::<syn::File>(quote! { #defs_code }) {
-			Ok(rust_file) => {
-				write!(f, "{}", prettyplease::unparse(&rust_file))?;
-			},
-			Err(_) => {
-				write!(f, "{}", quote! { #defs_code })?;
-			},
+		let code = quote! {
+			#(pub mod #submodules;)*
+			#defs_code
+		};
+
+		let content = match syn::parse2::<syn::File>(code.clone()) {
+			Ok(rust_file) => prettyplease::unparse(&rust_file),
+			Err(_) => code.to_string(),
 		};
 
+		write_if_changed(&p, &content)?;
+
 		Ok(p)
 	}
 
@@ -193,6 +462,7 @@ impl <'a> ModEmitter<'a> {
 
 	fn emit_record(&mut self, dfn: &'a DefinitionInfo, r: &'a RecordDefinition) -> Result<TokenStream, EmitError> {
 		let rec_name = convert_id_pascal(dfn.name.name());
+		let vis = definition_visibility(dfn);
 
 		let type_parameters = self.emit_type_parameters(&dfn.type_parameters);
 
@@ -208,6 +478,8 @@ impl <'a> ModEmitter<'a> {
 		self.process_record_ann(dfn, r, &mut derives, &mut attrs)?;
 		let attrs = attrs.into_iter().collect::<TokenStream>();
 
+		let default_impl = self.emit_record_default(dfn, r, &rec_name, is_unit, is_tuple)?;
+
 		if is_unit {
 			if !r.fields.is_empty() {
 				return Err(EmitError::UnitWithFields(dfn.name.as_ref().clone(), None));
@@ -217,27 +489,44 @@ impl <'a> ModEmitter<'a> {
 				#[allow(non_camel_case_types)]
 				#[derive(#(#derives),*)]
 				#attrs
-				pub struct #rec_name #type_parameters;
+				#vis struct #rec_name #type_parameters;
+
+				#default_impl
 			});
 		}
 
-		let fields = self.emit_fields(true, is_tuple, &r.fields)?;
+		let fields = self.emit_fields(dfn, true, is_tuple, &r.fields)?;
 
 		let struct_type =
 			if is_tuple {
 				quote! {
 					#[derive(#(#derives),*)]
 					#attrs
-					pub struct #rec_name #type_parameters(#fields);
+					#vis struct #rec_name #type_parameters(#fields);
+
+					#default_impl
 				}
 			}
 			else {
+				let builder =
+					if self.is_builder_enabled(dfn) {
+						let builder_name = record_builder_name(dfn);
+						self.emit_builder(dfn, &builder_name, &rec_name.to_string(), quote! { #rec_name }, &r.fields)?
+					}
+					else {
+						TokenStream::new()
+					};
+
 				quote! {
 					#[derive(#(#derives),*)]
 					#attrs
-					pub struct #rec_name #type_parameters {
+					#vis struct #rec_name #type_parameters {
 						#fields
 					}
+
+					#default_impl
+
+					#builder
 				}
 			};
 
@@ -250,6 +539,8 @@ impl <'a> ModEmitter<'a> {
 		derives.push(quote! { ::std::clone::Clone });
 		derives.push(quote! { ::std::cmp::PartialEq });
 
+		let mut dfn_derives = Vec::new();
+		let mut excluded_derives = Vec::new();
 		for ann in &dfn.annotations {
 			if ann.scope != "rust" {
 				continue;
@@ -258,14 +549,21 @@ impl <'a> ModEmitter<'a> {
 			let Ok(ann) = RustAnnRecord::decode_esexpr(ann.value.clone()) else { continue; };
 
 			match ann {
-				RustAnnRecord::Derive(derive) => {
-					derives.push(syn::parse_str(&derive)?);
-				},
-
+				RustAnnRecord::Derive(derive) => dfn_derives.push(derive),
+				RustAnnRecord::NoDerive(derive) => excluded_derives.push(derive),
 				_ => {}
 			}
 		}
 
+		self.add_derives(dfn, dfn_derives, &excluded_derives, derives)?;
+
+		attrs.push(deprecated_attr(dfn.deprecated.as_deref()));
+
+		if self.is_serde_enabled(dfn) {
+			derives.extend(serde_derives());
+			attrs.push(serde_crate_attr());
+		}
+
 		if let Some(esexpr_options) = &r.esexpr_options {
 			derives.push(quote! { ::esexpr::ESExprCodec });
 
@@ -276,30 +574,67 @@ impl <'a> ModEmitter<'a> {
 		Ok(())
 	}
 
+	// Derives from the package annotations are added unless the definition excludes them with no-derive.
+	fn add_derives(&self, dfn: &DefinitionInfo, dfn_derives: Vec<String>, excluded_derives: &[String], derives: &mut Vec<TokenStream>) -> Result<(), EmitError> {
+		let package_derives = self.package_derives.get(dfn.name.package_name())
+			.into_iter()
+			.flatten()
+			.filter(|derive| !excluded_derives.contains(derive))
+			.cloned();
+
+		let mut seen = HashSet::new();
+		for derive in dfn_derives.into_iter().chain(package_derives) {
+			if seen.insert(derive.clone()) {
+				derives.push(syn::parse_str(&derive)?);
+			}
+		}
+
+		Ok(())
+	}
+
 	fn emit_enum(&mut self, dfn: &'a DefinitionInfo, e: &'a EnumDefinition) -> Result<TokenStream, EmitError> {
 		let enum_name = convert_id_pascal(dfn.name.name());
+		let vis = definition_visibility(dfn);
 
 		let type_parameters = self.emit_type_parameters(&dfn.type_parameters);
 
 		let cases: TokenStream = e.cases.iter().map(|c| self.emit_enum_case(dfn, c)).collect::<Result<_, _>>()?;
 
 		let mut derives = Vec::new();
-		self.process_enum_ann(dfn, e, &mut derives)?;
+		let mut attrs = Vec::new();
+		self.process_enum_ann(dfn, e, &mut derives, &mut attrs)?;
+		let attrs = attrs.into_iter().collect::<TokenStream>();
+
+		let is_builder = self.is_builder_enabled(dfn);
+		let builders = e.cases.iter()
+			.filter(|c| is_builder && !self.is_enum_case_unit(c) && !self.is_enum_case_tuple(c))
+			.map(|c| {
+				let case_name = convert_id_pascal(&c.name);
+				let builder_name = enum_case_builder_name(dfn, c);
+				let type_name = format!("{}::{}", enum_name, case_name);
+				self.emit_builder(dfn, &builder_name, &type_name, quote! { #enum_name::#case_name }, &c.fields)
+			})
+			.collect::<Result<TokenStream, _>>()?;
 
 		Ok(quote! {
 			#[allow(non_camel_case_types)]
 			#[derive(#(#derives),*)]
-			pub enum #enum_name #type_parameters {
+			#attrs
+			#vis enum #enum_name #type_parameters {
 				#cases
 			}
+
+			#builders
 		})
 	}
 
-	fn process_enum_ann(&self, dfn: &DefinitionInfo, e: &EnumDefinition, derives: &mut Vec<TokenStream>) -> Result<(), EmitError>  {
+	fn process_enum_ann(&self, dfn: &DefinitionInfo, e: &EnumDefinition, derives: &mut Vec<TokenStream>, attrs: &mut Vec<TokenStream>) -> Result<(), EmitError>  {
 		derives.push(quote! { ::std::fmt::Debug });
 		derives.push(quote! { ::std::clone::Clone });
 		derives.push(quote! { ::std::cmp::PartialEq });
 
+		let mut dfn_derives = Vec::new();
+		let mut excluded_derives = Vec::new();
 		for ann in &dfn.annotations {
 			if ann.scope != "rust" {
 				continue;
@@ -308,12 +643,21 @@ impl <'a> ModEmitter<'a> {
 			let Ok(ann) = RustAnnEnum::decode_esexpr(ann.value.clone()) else { continue; };
 
 			match ann {
-				RustAnnEnum::Derive(derive) => {
-					derives.push(syn::parse_str(&derive)?);
-				},
+				RustAnnEnum::Derive(derive) => dfn_derives.push(derive),
+				RustAnnEnum::NoDerive(derive) => excluded_derives.push(derive),
+				RustAnnEnum::Serde | RustAnnEnum::Builder => {},
 			}
 		}
 
+		self.add_derives(dfn, dfn_derives, &excluded_derives, derives)?;
+
+		attrs.push(deprecated_attr(dfn.deprecated.as_deref()));
+
+		if self.is_serde_enabled(dfn) {
+			derives.extend(serde_derives());
+			attrs.push(serde_crate_attr());
+		}
+
 		if e.esexpr_options.is_some() {
 			derives.push(quote! { ::esexpr::ESExprCodec });
 		}
@@ -332,7 +676,7 @@ impl <'a> ModEmitter<'a> {
 		}
 
 		let mut attrs = Vec::new();
-		self.process_enum_case_ann(c, &mut attrs)?;
+		self.process_enum_case_ann(dfn, c, &mut attrs)?;
 		let attrs = attrs.into_iter().collect::<TokenStream>();
 
 		if is_unit {
@@ -346,7 +690,7 @@ impl <'a> ModEmitter<'a> {
 			})
 		}
 
-		let fields = self.emit_fields(false, is_tuple, &c.fields)?;
+		let fields = self.emit_fields(dfn, false, is_tuple, &c.fields)?;
 
 		if is_tuple {
 			Ok(quote! {
@@ -364,7 +708,13 @@ impl <'a> ModEmitter<'a> {
 		}
 	}
 
-	fn process_enum_case_ann(&self, c: &EnumCase, attrs: &mut Vec<TokenStream>) -> Result<(), EmitError> {
+	fn process_enum_case_ann(&self, dfn: &DefinitionInfo, c: &EnumCase, attrs: &mut Vec<TokenStream>) -> Result<(), EmitError> {
+		attrs.push(deprecated_attr(c.deprecated.as_deref()));
+
+		if self.is_serde_enabled(dfn) {
+			attrs.push(serde_rename_attr(&c.name));
+		}
+
 		if let Some(esexpr_options) = &c.esexpr_options {
 			match esexpr_options.case_type.as_ref() {
 				EsexprEnumCaseType::Constructor(name) => attrs.push(quote! { #[constructor = #name] }),
@@ -377,55 +727,87 @@ impl <'a> ModEmitter<'a> {
 
 	fn emit_simple_enum(&mut self, dfn: &'a DefinitionInfo, e: &'a SimpleEnumDefinition) -> Result<TokenStream, EmitError> {
 		let enum_name = convert_id_pascal(dfn.name.name());
+		let vis = definition_visibility(dfn);
+
+		let is_serde = self.is_serde_enabled(dfn);
+
+		let default_case = get_simple_enum_default_case(dfn);
+		if let Some(default_case) = &default_case {
+			if !e.cases.iter().any(|c| c.name == *default_case) {
+				return Err(EmitError::UnknownDefaultCase(dfn.name.as_ref().clone(), default_case.clone()));
+			}
+		}
 
 		let cases: TokenStream = e.cases.iter().map(|c| {
 			let id = convert_id_pascal(&c.name);
+			let deprecated = deprecated_attr(c.deprecated.as_deref());
+			let serde_rename = if is_serde { serde_rename_attr(&c.name) } else { quote! {} };
+			let default_attr = if default_case.as_ref() == Some(&c.name) { quote! { #[default] } } else { quote! {} };
 
 			if let Some(esexpr_options) = &c.esexpr_options {
 				let name = esexpr_options.name.as_str();
-				quote! { #[constructor = #name] #id, }
+				quote! { #deprecated #serde_rename #default_attr #[constructor = #name] #id, }
 			}
 			else {
-				quote! { #id, }
+				quote! { #deprecated #serde_rename #default_attr #id, }
 			}
 
 		}).collect();
 
 		let mut derives = Vec::new();
-		self.process_simple_enum_ann(dfn, e, &mut derives)?;
+		let mut attrs = Vec::new();
+		self.process_simple_enum_ann(dfn, e, &mut derives, &mut attrs)?;
+		let attrs = attrs.into_iter().collect::<TokenStream>();
 
 		Ok(quote! {
 			#[allow(non_camel_case_types)]
 			#[derive(#(#derives),*)]
-			#[simple_enum]
-			pub enum #enum_name {
+			#attrs
+			#vis enum #enum_name {
 				#cases
 			}
 		})
 	}
 
-	fn process_simple_enum_ann(&self, dfn: &DefinitionInfo, e: &SimpleEnumDefinition, derives: &mut Vec<TokenStream>) -> Result<(), EmitError>  {
+	fn process_simple_enum_ann(&self, dfn: &DefinitionInfo, e: &SimpleEnumDefinition, derives: &mut Vec<TokenStream>, attrs: &mut Vec<TokenStream>) -> Result<(), EmitError>  {
 		derives.push(quote! { ::std::fmt::Debug });
 		derives.push(quote! { ::std::clone::Clone });
 		derives.push(quote! { ::std::marker::Copy });
 		derives.push(quote! { ::std::cmp::PartialEq });
 
+		let mut dfn_derives = Vec::new();
+		let mut excluded_derives = Vec::new();
 		for ann in &dfn.annotations {
 			if ann.scope != "rust" {
 				continue;
 			}
 
-			let Ok(ann) = RustAnnEnum::decode_esexpr(ann.value.clone()) else { continue; };
+			let Ok(ann) = RustAnnSimpleEnum::decode_esexpr(ann.value.clone()) else { continue; };
 
 			match ann {
-				RustAnnEnum::Derive(derive) => {
-					derives.push(syn::parse_str(&derive)?);
-				},
+				RustAnnSimpleEnum::Derive(derive) => dfn_derives.push(derive),
+				RustAnnSimpleEnum::NoDerive(derive) => excluded_derives.push(derive),
+				RustAnnSimpleEnum::Serde => {},
+				RustAnnSimpleEnum::DefaultCase(_) => {},
 			}
 		}
 
+		self.add_derives(dfn, dfn_derives, &excluded_derives, derives)?;
+
+		if get_simple_enum_default_case(dfn).is_some() {
+			derives.push(quote! { ::std::default::Default });
+		}
+
+		attrs.push(deprecated_attr(dfn.deprecated.as_deref()));
+
+		if self.is_serde_enabled(dfn) {
+			derives.extend(serde_derives());
+			attrs.push(serde_crate_attr());
+		}
+
 		if e.esexpr_options.is_some() {
 			derives.push(quote! { ::esexpr::ESExprCodec });
+			attrs.push(quote! { #[simple_enum] });
 		}
 
 		Ok(())
@@ -437,6 +819,8 @@ impl <'a> ModEmitter<'a> {
 		use interface::public_trait::*;
 		use interface::mapper::*;
 
+		let allow_deprecated = allow_deprecated_attr(dfn);
+
 		let iface = InterfaceTraitInterfaceEmitter {
 			type_emitter: DefaultTypeEmitter(self),
 		}.emit_trait(dfn, i)?;
@@ -455,7 +839,7 @@ impl <'a> ModEmitter<'a> {
 			mod_emitter: self,
 			dfn,
 			iface: i,
-		}.emit_mapper_type()?.into_iter().map(|item| quote! { #item }).collect::<TokenStream>();
+		}.emit_mapper_type()?.into_iter().map(|item| quote! { #allow_deprecated #item }).collect::<TokenStream>();
 
 		let public_impl = InterfacePublicImplEmitter {
 			dfn,
@@ -464,21 +848,27 @@ impl <'a> ModEmitter<'a> {
 		}.emit_interface_public_impl()?;
 
 		let if_name = convert_id_pascal(dfn.name.name());
+		let vis = definition_visibility(dfn);
 		let if_name_erased = format_ident!("{}_Erased", if_name);
 
 		let type_parameters = self.emit_type_parameters(&dfn.type_parameters);
 		let type_args = self.emit_type_parameters_as_arguments(&dfn.type_parameters);
 
+		let deprecated = deprecated_attr(dfn.deprecated.as_deref());
+
 		Ok(quote! {
 			#iface
 			#erased
+			#allow_deprecated
 			#erased_impl
 			#mapper
 
-			pub struct #if_name #type_parameters {
+			#deprecated
+			#vis struct #if_name #type_parameters {
 				erased: ::std::sync::Arc<dyn #if_name_erased #type_args + ::std::marker::Send + ::std::marker::Sync + 'static>,
 			}
 
+			#allow_deprecated
 			impl #type_parameters ::std::clone::Clone for #if_name #type_args {
 				fn clone(&self) -> Self {
 					#if_name {
@@ -487,6 +877,7 @@ impl <'a> ModEmitter<'a> {
 				}
 			}
 
+			#allow_deprecated
 			#public_impl
 		})
 	}
@@ -545,16 +936,275 @@ impl <'a> ModEmitter<'a> {
 		}
 	}
 
-	fn emit_fields(&mut self, use_pub: bool, is_tuple: bool, fields: &'a [Box<RecordField>]) -> Result<TokenStream, EmitError> {
-		fields.iter().map(|f| self.emit_field(use_pub, is_tuple, f)).collect()
+	// Builders are only generated for named fields.
+	// The type name and constructor refer to either a record or an enum case.
+	fn emit_builder(&self, dfn: &DefinitionInfo, builder_name: &syn::Ident, type_name: &str, constructor: TokenStream, fields: &[Box<RecordField>]) -> Result<TokenStream, EmitError> {
+		let vis = definition_visibility(dfn);
+		let dfn_name = convert_id_pascal(dfn.name.name());
+
+		let type_parameters = self.emit_type_parameters(&dfn.type_parameters);
+		let (impl_generics, ty_generics, _) = type_parameters.split_for_impl();
+
+		let mut field_names = Vec::new();
+		let mut builder_fields = Vec::new();
+		let mut setters = Vec::new();
+		let mut values = Vec::new();
+		let mut required = Vec::new();
+		let mut required_names = Vec::new();
+
+		for field in fields {
+			let field_name = convert_id_snake(&field.name);
+			let boxing = self.get_field_boxing(dfn, field);
+			let field_type = DefaultTypeEmitter(self).emit_boxed_type_expr(&field.field_type, boxing)?;
+			let deprecated = deprecated_attr(field.deprecated.as_deref());
+
+			builder_fields.push(quote! { #field_name: ::std::option::Option<#field_type>, });
+
+			setters.push(quote! {
+				#deprecated
+				pub fn #field_name(mut self, value: impl ::std::convert::Into<#field_type>) -> Self {
+					self.#field_name = ::std::option::Option::Some(value.into());
+					self
+				}
+			});
+
+			match self.get_field_default(field, boxing)? {
+				Some(default_value) => values.push(quote! { #field_name: self.#field_name.unwrap_or_else(|| #default_value), }),
+				None => {
+					values.push(quote! { #field_name, });
+					required.push(field_name.clone());
+					required_names.push(convert_id_snake_str(&field.name));
+				},
+			}
+
+			field_names.push(field_name);
+		}
+
+		let build_body =
+			if required.is_empty() {
+				quote! {
+					::std::result::Result::Ok(#constructor { #(#values)* })
+				}
+			}
+			else {
+				quote! {
+					match (#(self.#required,)*) {
+						(#(::std::option::Option::Some(#required),)*) => ::std::result::Result::Ok(#constructor { #(#values)* }),
+						(#(#required,)*) => ::std::result::Result::Err(::noble_idl_runtime::MissingFieldsError {
+							type_name: #type_name,
+							fields: [ #(#required.is_none().then_some(#required_names)),* ].into_iter().flatten().collect(),
+						}),
+					}
+				}
+			};
+
+		// Enum case builders may not use every type parameter of the enum.
+		let type_args = dfn.type_parameters.iter().map(|tp| convert_id_pascal(tp.name()));
+		let phantom_type = quote! { ::std::marker::PhantomData<fn() -> (#(#type_args,)*)> };
+
+		let allow_deprecated = allow_deprecated_attr(dfn);
+
+		Ok(quote! {
+			#[allow(non_camel_case_types)]
+			#vis struct #builder_name #type_parameters {
+				#(#builder_fields)*
+				_phantom: #phantom_type,
+			}
+
+			impl #impl_generics ::std::default::Default for #builder_name #ty_generics {
+				fn default() -> Self {
+					#builder_name {
+						#(#field_names: ::std::option::Option::None,)*
+						_phantom: ::std::marker::PhantomData,
+					}
+				}
+			}
+
+			// Default values are emitted as conversions that are often redundant.
+			#[allow(clippy::useless_conversion, clippy::redundant_closure, clippy::unnecessary_lazy_evaluations, clippy::unit_arg)]
+			#allow_deprecated
+			impl #impl_generics #builder_name #ty_generics {
+				#(#setters)*
+
+				pub fn build(self) -> ::std::result::Result<#dfn_name #ty_generics, ::noble_idl_runtime::MissingFieldsError> {
+					#build_body
+				}
+			}
+		})
+	}
+
+	// Records get a Default implementation when every field has a default in the wire format or a type that implements Default.
+	fn emit_record_default(&self, dfn: &'a DefinitionInfo, r: &'a RecordDefinition, rec_name: &syn::Ident, is_unit: bool, is_tuple: bool) -> Result<TokenStream, EmitError> {
+		if self.derives_default(dfn) || !self.is_default_record(dfn, r, &mut HashSet::new()) {
+			return Ok(quote! {});
+		}
+
+		let mut field_names = Vec::new();
+		let mut values = Vec::new();
+		for field in &r.fields {
+			let boxing = self.get_field_boxing(dfn, field);
+			let value = self.get_field_default(field, boxing)?
+				.unwrap_or_else(|| parse_quote! { ::std::default::Default::default() });
+
+			field_names.push(convert_id_snake(&field.name));
+			values.push(value);
+		}
+
+		let value =
+			if is_unit { quote! { #rec_name } }
+			else if is_tuple { quote! { #rec_name(#(#values),*) } }
+			else { quote! { #rec_name { #(#field_names: #values),* } } };
+
+		let type_parameters = self.emit_type_parameters(&dfn.type_parameters);
+		let (_, ty_generics, _) = type_parameters.split_for_impl();
+
+		let mut bounded_type_parameters = type_parameters.clone();
+		for param in bounded_type_parameters.type_params_mut() {
+			param.bounds.push(parse_quote! { ::std::default::Default });
+		}
+		let (impl_generics, _, _) = bounded_type_parameters.split_for_impl();
+
+		let allow_deprecated = allow_deprecated_attr(dfn);
+
+		Ok(quote! {
+			#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+			#allow_deprecated
+			impl #impl_generics ::std::default::Default for #rec_name #ty_generics {
+				fn default() -> Self {
+					#value
+				}
+			}
+		})
+	}
+
+	// Records that are part of a cycle being checked are not considered to have a default.
+	fn is_default_record(&self, dfn: &'a DefinitionInfo, r: &'a RecordDefinition, visiting: &mut HashSet<&'a QualifiedName>) -> bool {
+		if !visiting.insert(dfn.name.as_ref()) {
+			return false;
+		}
+
+		let result = r.fields.iter().all(|f| has_field_default(f) || self.is_default_type(&f.field_type, visiting));
+
+		visiting.remove(&dfn.name.as_ref());
+		result
+	}
+
+	fn is_default_type(&self, t: &'a TypeExpr, visiting: &mut HashSet<&'a QualifiedName>) -> bool {
+		match t {
+			// Type parameters are bounded by Default.
+			TypeExpr::TypeParameter { .. } => true,
+
+			TypeExpr::DefinedType(name, args) => {
+				let Some(dfn) = self.definition_map.get(name.as_ref()).copied() else { return false; };
+
+				match dfn.definition.as_ref() {
+					Definition::Record(r) =>
+						(self.derives_default(dfn) || self.is_default_record(dfn, r, visiting)) &&
+							args.iter().all(|arg| self.is_default_type(arg, visiting)),
+
+					Definition::SimpleEnum(_) => self.derives_default(dfn) || get_simple_enum_default_case(dfn).is_some(),
+
+					Definition::ExternType(_) =>
+						name.package_name().0 == ["nobleidl", "core"] &&
+							DEFAULT_CORE_TYPES.contains(&name.name()),
+
+					Definition::Enum(_) | Definition::Interface(_) | Definition::ExceptionType(_) => false,
+				}
+			},
+		}
+	}
+
+	// Types that already derive Default through annotations keep the derived implementation.
+	fn derives_default(&self, dfn: &DefinitionInfo) -> bool {
+		let mut dfn_derives = Vec::new();
+		let mut excluded_derives = Vec::new();
+		for ann in dfn.annotations.iter().filter(|ann| ann.scope == "rust") {
+			let (derive, no_derive) = match dfn.definition.as_ref() {
+				Definition::Record(_) => match RustAnnRecord::decode_esexpr(ann.value.clone()) {
+					Ok(RustAnnRecord::Derive(derive)) => (Some(derive), None),
+					Ok(RustAnnRecord::NoDerive(derive)) => (None, Some(derive)),
+					_ => (None, None),
+				},
+				Definition::SimpleEnum(_) => match RustAnnSimpleEnum::decode_esexpr(ann.value.clone()) {
+					Ok(RustAnnSimpleEnum::Derive(derive)) => (Some(derive), None),
+					Ok(RustAnnSimpleEnum::NoDerive(derive)) => (None, Some(derive)),
+					_ => (None, None),
+				},
+				_ => (None, None),
+			};
+
+			dfn_derives.extend(derive);
+			excluded_derives.extend(no_derive);
+		}
+
+		let package_derives = self.package_derives.get(dfn.name.package_name())
+			.into_iter()
+			.flatten()
+			.filter(|derive| !excluded_derives.contains(derive));
+
+		dfn_derives.iter()
+			.chain(package_derives)
+			.any(|derive| derive.rsplit("::").next() == Some("Default"))
+	}
+
+	// Optional fields default to none and default-value fields use their declared value.
+	fn get_field_default(&self, field: &RecordField, boxing: TypeBoxing) -> Result<Option<syn::Expr>, EmitError> {
+		if let Some(esexpr_options) = &field.esexpr_options {
+			let element_type = match esexpr_options.kind.as_ref() {
+				EsexprRecordFieldKind::Positional(mode) => match mode.as_ref() {
+					EsexprRecordPositionalMode::Optional(element_type) => Some(element_type),
+					_ => None,
+				},
+
+				EsexprRecordFieldKind::Keyword(_, mode) => match mode.as_ref() {
+					EsexprRecordKeywordMode::Optional(element_type) => Some(element_type),
+					EsexprRecordKeywordMode::DefaultValue(value) => return self.emit_value(value, boxing).map(Some),
+					_ => None,
+				},
+
+				_ => None,
+			};
+
+			if let Some(element_type) = element_type {
+				// The element type refers to the type parameters of the optional container.
+				let mut element_type = element_type.as_ref().clone();
+				if let TypeExpr::DefinedType(name, args) = field.field_type.as_ref() {
+					if let Some(container) = self.definition_map.get(name.as_ref()) {
+						let mapping = container.type_parameters.iter()
+							.map(|tp| tp.name())
+							.zip(args.iter().map(AsRef::as_ref))
+							.collect::<HashMap<_, _>>();
+
+						if element_type.substitute(&mapping) {
+							return self.emit_optional_value(&field.field_type, &element_type, None, boxing).map(Some);
+						}
+					}
+				}
+			}
+		}
+
+		match field.field_type.as_ref() {
+			TypeExpr::DefinedType(name, args) if name.package_name().0 == ["nobleidl", "core"] && name.name() == "optional-field" && args.len() == 1 =>
+				self.emit_optional_value(&field.field_type, &args[0], None, boxing).map(Some),
+
+			_ => Ok(None),
+		}
+	}
+
+	fn emit_fields(&mut self, dfn: &'a DefinitionInfo, use_pub: bool, is_tuple: bool, fields: &'a [Box<RecordField>]) -> Result<TokenStream, EmitError> {
+		fields.iter().map(|f| self.emit_field(dfn, use_pub, is_tuple, f)).collect()
 	}
 
-	fn emit_field(&mut self, use_pub: bool, is_tuple: bool, field: &'a RecordField) -> Result<TokenStream, EmitError> {
+	fn emit_field(&mut self, dfn: &'a DefinitionInfo, use_pub: bool, is_tuple: bool, field: &'a RecordField) -> Result<TokenStream, EmitError> {
 		let field_name = convert_id_snake(&field.name);
-		let field_type = self.emit_type_expr(&field.field_type)?;
+		let boxing = self.get_field_boxing(dfn, field);
+		let field_type = DefaultTypeEmitter(self).emit_boxed_type_expr(&field.field_type, boxing)?;
 
 		let mut attrs = Vec::new();
-		self.process_field_ann(field, &mut attrs)?;
+		self.process_field_ann(field, boxing, &mut attrs)?;
+		if self.is_serde_enabled(dfn) {
+			attrs.push(serde_field_attrs(field, is_tuple));
+		}
 		let attrs = attrs.into_iter().collect::<TokenStream>();
 
 		let pub_kw = if use_pub { quote! { pub } } else { quote!{} };
@@ -573,7 +1223,9 @@ impl <'a> ModEmitter<'a> {
 		}
 	}
 
-	fn process_field_ann(&self, field: &RecordField, attrs: &mut Vec<TokenStream>) -> Result<(), EmitError> {
+	fn process_field_ann(&self, field: &RecordField, boxing: TypeBoxing, attrs: &mut Vec<TokenStream>) -> Result<(), EmitError> {
+		attrs.push(deprecated_attr(field.deprecated.as_deref()));
+
 		if let Some(esexpr_options) = &field.esexpr_options {
 			match esexpr_options.kind.as_ref() {
 				EsexprRecordFieldKind::Positional(mode) => {
@@ -591,7 +1243,14 @@ impl <'a> ModEmitter<'a> {
 						EsexprRecordKeywordMode::Required => {},
 
 						EsexprRecordKeywordMode::DefaultValue(default_value) => {
-							let value = self.emit_value(default_value)?;
+							let value = self.emit_value(default_value, boxing)?;
+
+							// The derive does not accept a bare struct literal as the default value.
+							let value: syn::Expr = match value {
+								syn::Expr::Struct(_) => parse_quote! { (#value) },
+								value => value,
+							};
+
 							let value_str = value.into_token_stream().to_string();
 
 							attrs.push(quote! { #[default_value = #value_str] });
@@ -610,19 +1269,44 @@ impl <'a> ModEmitter<'a> {
 		Ok(())
 	}
 
+	// Fields are boxed when they refer back to the cycle containing their definition, unless overridden.
+	fn get_field_boxing(&self, dfn: &DefinitionInfo, field: &RecordField) -> TypeBoxing<'_> {
+		match get_field_boxing_override(field) {
+			Some(RustAnnRecordField::Boxed) => TypeBoxing::All,
+			Some(RustAnnRecordField::Unboxed) => TypeBoxing::None,
+			None => self.get_default_boxing(dfn),
+		}
+	}
+
+	// Packages annotated with @rust:(boxed) box every value type reference, as older versions of the backend did.
+	fn get_default_boxing(&self, dfn: &DefinitionInfo) -> TypeBoxing<'_> {
+		if self.boxed_packages.contains(dfn.name.package_name()) {
+			return TypeBoxing::All;
+		}
+
+		self.recursive_types.get(dfn.name.as_ref())
+			.map(TypeBoxing::Recursive)
+			.unwrap_or(TypeBoxing::None)
+	}
+
 	fn emit_exception_type(&self, dfn: &DefinitionInfo, ex: &ExceptionTypeDefinition) -> Result<TokenStream, EmitError> {
 		let name = convert_id_pascal(dfn.name.name());
-		let info = self.emit_type_expr(&ex.information)?;
+		let vis = definition_visibility(dfn);
+		let info = DefaultTypeEmitter(self).emit_boxed_type_expr(&ex.information, self.get_default_boxing(dfn))?;
+		let deprecated = deprecated_attr(dfn.deprecated.as_deref());
+		let allow_deprecated = allow_deprecated_attr(dfn);
 
 		Ok(quote! {
 			#[derive(::std::fmt::Debug, std::clone::Clone)]
-			pub struct #name {
+			#deprecated
+			#vis struct #name {
 				pub information: #info,
 				pub message: ::std::option::Option<::std::string::String>,
 				pub backtrace: ::std::sync::Arc<::std::backtrace::Backtrace>,
 				pub source: ::std::option::Option<::std::sync::Arc<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send + 'static>>,
 			}
 
+			#allow_deprecated
 			impl ::std::fmt::Display for #name {
 				fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
 					if let ::std::option::Option::Some(msg) = &self.message {
@@ -634,6 +1318,7 @@ impl <'a> ModEmitter<'a> {
 				}
 			}
 
+			#allow_deprecated
 			impl ::std::error::Error for #name {
 				fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
 					self.source.as_deref().map(|s| s as _)
@@ -650,15 +1335,16 @@ impl <'a> ModEmitter<'a> {
 		DefaultTypeEmitter(self).emit_type_expr(t)
 	}
 
-	fn emit_value(&self, value: &EsexprDecodedValue) -> Result<syn::Expr, EmitError> {
+	// The boxing must match the position that the value is assigned to.
+	fn emit_value(&self, value: &EsexprDecodedValue, boxing: TypeBoxing) -> Result<syn::Expr, EmitError> {
 		match value {
-			EsexprDecodedValue::Record { t, fields } => self.emit_record_value(t, fields),
-			EsexprDecodedValue::Enum { t, case_name, fields } => self.emit_enum_value(t, case_name, fields),
+			EsexprDecodedValue::Record { t, fields } => self.emit_record_value(t, fields, boxing),
+			EsexprDecodedValue::Enum { t, case_name, fields } => self.emit_enum_value(t, case_name, fields, boxing),
 			EsexprDecodedValue::SimpleEnum { t, case_name } => self.emit_simple_enum_value(t, case_name),
-			EsexprDecodedValue::Optional { t, element_type, value } => self.emit_optional_value(t, element_type, value.as_deref()),
-			EsexprDecodedValue::Vararg { t, element_type, values } => self.emit_vararg_value(t, element_type, values),
-			EsexprDecodedValue::Dict { t, element_type, values } => self.emit_dict_value(t, element_type, values),
-			EsexprDecodedValue::BuildFrom { t, from_type, from_value } => self.emit_build_from(t, from_type, &**from_value),
+			EsexprDecodedValue::Optional { t, element_type, value } => self.emit_optional_value(t, element_type, value.as_deref(), boxing),
+			EsexprDecodedValue::Vararg { t, element_type, values } => self.emit_vararg_value(t, element_type, values, boxing),
+			EsexprDecodedValue::Dict { t, element_type, values } => self.emit_dict_value(t, element_type, values, boxing),
+			EsexprDecodedValue::BuildFrom { t, from_type, from_value } => self.emit_build_from(t, from_type, &**from_value, boxing),
 			EsexprDecodedValue::FromBool { t, b } => self.emit_literal_primitive::<bool>(t, parse_quote!(::std::primitive::bool), *b),
 			EsexprDecodedValue::FromInt { t, i, min_int, max_int } => self.emit_literal_int(t, i, min_int.as_ref(), max_int.as_ref()),
 			EsexprDecodedValue::FromStr { t, s } => self.emit_literal_primitive::<&str>(t, parse_quote!(&'static ::std::primitive::str), s),
@@ -704,27 +1390,45 @@ impl <'a> ModEmitter<'a> {
 
 			EsexprDecodedValue::FromNull { t, level: None, max_level } =>
 				self.emit_literal_null(t, &BigUint::ZERO, max_level.as_ref()),
+
+			EsexprDecodedValue::FromEsexpr { t, .. } =>
+				Err(EmitError::InvalidLiteralForType(t.as_ref().clone())),
 		}
 	}
 
-	fn emit_record_value(&self, t: &TypeExpr, field_values: &[Box<EsexprDecodedFieldValue>]) -> Result<syn::Expr, EmitError> {
+	fn emit_record_value(&self, t: &TypeExpr, field_values: &[Box<EsexprDecodedFieldValue>], boxing: TypeBoxing) -> Result<syn::Expr, EmitError> {
+		let fields = match self.get_value_definition(t) {
+			Some((dfn, Definition::Record(r))) => self.emit_field_values(dfn, &r.fields, field_values, boxing)?,
+			_ => return Err(EmitError::InvalidLiteralForType(t.clone())),
+		};
+
 		let value = syn::Expr::Struct(syn::ExprStruct {
 			attrs: vec![],
 			qself: None,
-			path: DefaultTypeEmitter(self).get_literal_type_path(t)?,
+			path: DefaultTypeEmitter(self).get_boxed_literal_type_path(t, inherited_boxing(boxing))?,
 			brace_token: syn::token::Brace::default(),
-			fields: self.emit_field_values(field_values)?.into_iter().collect(),
+			fields: fields.into_iter().collect(),
 			dot2_token: None,
 			rest: None,
 		});
 
-		Ok(parse_quote! {
-			::std::boxed::Box::new(#value)
-		})
+		Ok(self.box_value(t, value, boxing))
 	}
 
-	fn emit_enum_value(&self, t: &TypeExpr, case_name: &str, field_values: &[Box<EsexprDecodedFieldValue>]) -> Result<syn::Expr, EmitError> {
-		let mut path = DefaultTypeEmitter(self).get_literal_type_path(t)?;
+	fn emit_enum_value(&self, t: &TypeExpr, case_name: &str, field_values: &[Box<EsexprDecodedFieldValue>], boxing: TypeBoxing) -> Result<syn::Expr, EmitError> {
+		let fields = match self.get_value_definition(t) {
+			Some((dfn, Definition::Enum(e))) => {
+				let c = e.cases.iter()
+					.find(|c| c.name == case_name)
+					.ok_or_else(|| EmitError::InvalidLiteralForType(t.clone()))?;
+
+				self.emit_field_values(dfn, &c.fields, field_values, boxing)?
+			},
+			_ => return Err(EmitError::InvalidLiteralForType(t.clone())),
+		};
+
+
+		let mut path = DefaultTypeEmitter(self).get_boxed_literal_type_path(t, inherited_boxing(boxing))?;
 		path.segments.push(syn::PathSegment {
 			ident: convert_id_pascal(case_name),
 			arguments: syn::PathArguments::None,
@@ -735,14 +1439,29 @@ impl <'a> ModEmitter<'a> {
 			qself: None,
 			path,
 			brace_token: Default::default(),
-			fields: self.emit_field_values(field_values)?.into_iter().collect(),
+			fields: fields.into_iter().collect(),
 			dot2_token: None,
 			rest: None,
 		});
 
-		Ok(parse_quote! {
-			::std::boxed::Box::new(#value)
-		})
+		Ok(self.box_value(t, value, boxing))
+	}
+
+	fn get_value_definition(&self, t: &TypeExpr) -> Option<(&'a DefinitionInfo, &'a Definition)> {
+		let TypeExpr::DefinedType(name, _) = t else { return None; };
+		let dfn = *self.definition_map.get(name.as_ref())?;
+		Some((dfn, dfn.definition.as_ref()))
+	}
+
+	fn box_value(&self, t: &TypeExpr, value: syn::Expr, boxing: TypeBoxing) -> syn::Expr {
+		if DefaultTypeEmitter(self).is_type_boxed(t, boxing) {
+			parse_quote! {
+				::std::boxed::Box::new(#value)
+			}
+		}
+		else {
+			value
+		}
 	}
 
 	fn emit_simple_enum_value(&self, t: &TypeExpr, case_name: &str) -> Result<syn::Expr, EmitError> {
@@ -753,13 +1472,23 @@ impl <'a> ModEmitter<'a> {
 		});
 
 		Ok(parse_quote! {
-			::std::boxed::Box::new(#path)
+			#path
 		})
 	}
 
-	fn emit_field_values(&self, field_values: &[Box<EsexprDecodedFieldValue>]) -> Result<Vec<syn::FieldValue>, EmitError> {
+	// Values nested in a value that boxes everything also box everything, as their types may be type arguments of that value.
+	fn emit_field_values(&self, dfn: &DefinitionInfo, fields: &[Box<RecordField>], field_values: &[Box<EsexprDecodedFieldValue>], value_boxing: TypeBoxing) -> Result<Vec<syn::FieldValue>, EmitError> {
 		field_values.iter().map(|fv| {
-			let value = self.emit_value(&fv.value)?;
+			let boxing = match value_boxing {
+				TypeBoxing::All => TypeBoxing::All,
+				TypeBoxing::None | TypeBoxing::Recursive(_) =>
+					fields.iter()
+						.find(|f| f.name == fv.name)
+						.map(|f| self.get_field_boxing(dfn, f))
+						.unwrap_or(TypeBoxing::None),
+			};
+
+			let value = self.emit_value(&fv.value, boxing)?;
 			Ok(syn::FieldValue {
 				attrs: vec![],
 				member: syn::Member::Named(convert_id_snake(&fv.name)),
@@ -769,10 +1498,11 @@ impl <'a> ModEmitter<'a> {
 		}).collect::<Result<Vec<_>, _>>()
 	}
 
-	fn emit_optional_value(&self, optional_type: &TypeExpr, element_type: &TypeExpr, value: Option<&EsexprDecodedValue>) -> Result<syn::Expr, EmitError> {
-		let t = self.emit_type_expr(optional_type)?;
-		let et = self.emit_type_expr(element_type)?;
-		let v = value.map(|v| self.emit_value(v)).transpose()?;
+	fn emit_optional_value(&self, optional_type: &TypeExpr, element_type: &TypeExpr, value: Option<&EsexprDecodedValue>, boxing: TypeBoxing) -> Result<syn::Expr, EmitError> {
+		let t = DefaultTypeEmitter(self).emit_boxed_type_expr(optional_type, boxing)?;
+		let element_boxing = self.get_element_boxing(optional_type, element_type, boxing);
+		let et = DefaultTypeEmitter(self).emit_boxed_type_expr(element_type, element_boxing)?;
+		let v = value.map(|v| self.emit_value(v, element_boxing)).transpose()?;
 		let v: syn::Expr = match v {
 			Some(v) => parse_quote! { std::option::Option::Some(#v) },
 			None => parse_quote! { ::std::option::Option::None },
@@ -783,10 +1513,11 @@ impl <'a> ModEmitter<'a> {
 		})
 	}
 
-	fn emit_vararg_value(&self, vararg_type: &TypeExpr, element_type: &TypeExpr, values: &[Box<EsexprDecodedValue>]) -> Result<syn::Expr, EmitError> {
-		let t = self.emit_type_expr(vararg_type)?;
-		let et = self.emit_type_expr(element_type)?;
-		let v = values.iter().map(|v| self.emit_value(v)).collect::<Result<Vec<_>, _>>()?;
+	fn emit_vararg_value(&self, vararg_type: &TypeExpr, element_type: &TypeExpr, values: &[Box<EsexprDecodedValue>], boxing: TypeBoxing) -> Result<syn::Expr, EmitError> {
+		let t = DefaultTypeEmitter(self).emit_boxed_type_expr(vararg_type, boxing)?;
+		let element_boxing = self.get_element_boxing(vararg_type, element_type, boxing);
+		let et = DefaultTypeEmitter(self).emit_boxed_type_expr(element_type, element_boxing)?;
+		let v = values.iter().map(|v| self.emit_value(v, element_boxing)).collect::<Result<Vec<_>, _>>()?;
 		let v: syn::Expr = parse_quote! { ::std::vec![#(#v),*] };
 
 
@@ -796,11 +1527,13 @@ impl <'a> ModEmitter<'a> {
 		})
 	}
 
-	fn emit_dict_value(&self, dict_type: &TypeExpr, element_type: &TypeExpr, values: &HashMap<String, Box<EsexprDecodedValue>>) -> Result<syn::Expr, EmitError> {
-		let t = self.emit_type_expr(dict_type)?;
-		let et = self.emit_type_expr(element_type)?;
-		let v = values.iter().map(|(k, v)| {
-			let v = self.emit_value(v)?;
+	fn emit_dict_value(&self, dict_type: &TypeExpr, element_type: &TypeExpr, values: &HashMap<String, Box<EsexprDecodedValue>>, boxing: TypeBoxing) -> Result<syn::Expr, EmitError> {
+		let t = DefaultTypeEmitter(self).emit_boxed_type_expr(dict_type, boxing)?;
+		let element_boxing = self.get_element_boxing(dict_type, element_type, boxing);
+		let et = DefaultTypeEmitter(self).emit_boxed_type_expr(element_type, element_boxing)?;
+		let values = values.iter().collect::<BTreeMap<_, _>>();
+		let v = values.into_iter().map(|(k, v)| {
+			let v = self.emit_value(v, element_boxing)?;
 			Ok(parse_quote! { (<::std::primitive::str as std::borrow::ToOwned>::to_owned(#k), #v) })
 		}).collect::<Result<Vec<syn::Expr>, EmitError>>()?;
 
@@ -811,16 +1544,29 @@ impl <'a> ModEmitter<'a> {
 		})
 	}
 
-	fn emit_build_from(&self, built_type: &TypeExpr, from_type: &TypeExpr, value: &EsexprDecodedValue) -> Result<syn::Expr, EmitError> {
-		let t = self.emit_type_expr(built_type)?;
-		let ft = self.emit_type_expr(from_type)?;
-		let v = self.emit_value(value)?;
+	// The intermediate value is not stored anywhere, so it is only boxed when everything is.
+	fn emit_build_from(&self, built_type: &TypeExpr, from_type: &TypeExpr, value: &EsexprDecodedValue, boxing: TypeBoxing) -> Result<syn::Expr, EmitError> {
+		let from_boxing = inherited_boxing(boxing);
+
+		let t = DefaultTypeEmitter(self).emit_boxed_type_expr(built_type, boxing)?;
+		let ft = DefaultTypeEmitter(self).emit_boxed_type_expr(from_type, from_boxing)?;
+		let v = self.emit_value(value, from_boxing)?;
 
 		Ok(parse_quote! {
 			<#t as ::std::convert::From<#ft>>::from(#v)
 		})
 	}
 
+	// Elements use the boxing of the matching type argument of their container.
+	fn get_element_boxing<'b>(&self, container_type: &TypeExpr, element_type: &TypeExpr, boxing: TypeBoxing<'b>) -> TypeBoxing<'b> {
+		let TypeExpr::DefinedType(name, args) = container_type else { return TypeBoxing::None; };
+
+		match args.iter().position(|arg| **arg == *element_type) {
+			Some(index) => type_argument_boxing(self.definition_map.get(name.as_ref()).copied(), index, boxing),
+			None => TypeBoxing::None,
+		}
+	}
+
 	fn emit_literal_primitive<T: quote::ToTokens>(&self, t: &TypeExpr, prim_type: syn::Type, value: T) -> Result<syn::Expr, EmitError> {
 		self.emit_literal_primitive_expr::<T, T>(t, prim_type, value)
 	}
@@ -954,6 +1700,67 @@ impl <'a> ModEmitter<'a> {
 			})
 	}
 
+	// Serde support is enabled for every record and enum of a package, or for individual definitions.
+	fn is_serde_enabled(&self, dfn: &DefinitionInfo) -> bool {
+		if self.serde_packages.contains(dfn.name.package_name()) {
+			return !matches!(dfn.definition.as_ref(), Definition::ExternType(_) | Definition::Interface(_) | Definition::ExceptionType(_));
+		}
+
+		dfn.annotations.iter()
+			.filter(|ann| ann.scope == "rust")
+			.any(|ann| match dfn.definition.as_ref() {
+				Definition::Record(_) => matches!(RustAnnRecord::decode_esexpr(ann.value.clone()), Ok(RustAnnRecord::Serde)),
+				Definition::Enum(_) => matches!(RustAnnEnum::decode_esexpr(ann.value.clone()), Ok(RustAnnEnum::Serde)),
+				Definition::SimpleEnum(_) => matches!(RustAnnSimpleEnum::decode_esexpr(ann.value.clone()), Ok(RustAnnSimpleEnum::Serde)),
+				_ => false,
+			})
+	}
+
+	fn is_builder_enabled(&self, dfn: &DefinitionInfo) -> bool {
+		if self.builder_packages.contains(dfn.name.package_name()) {
+			return matches!(dfn.definition.as_ref(), Definition::Record(_) | Definition::Enum(_));
+		}
+
+		dfn.annotations.iter()
+			.filter(|ann| ann.scope == "rust")
+			.any(|ann| match dfn.definition.as_ref() {
+				Definition::Record(_) => matches!(RustAnnRecord::decode_esexpr(ann.value.clone()), Ok(RustAnnRecord::Builder)),
+				Definition::Enum(_) => matches!(RustAnnEnum::decode_esexpr(ann.value.clone()), Ok(RustAnnEnum::Builder)),
+				_ => false,
+			})
+	}
+
+	// Builder names are derived from the type and case names, so they can clash with each other or with the definitions in the module.
+	fn check_builder_names(&self, definitions: &[&DefinitionInfo]) -> Result<(), EmitError> {
+		let mut names = definitions.iter()
+			.map(|dfn| convert_id_pascal(dfn.name.name()).to_string())
+			.collect::<HashSet<_>>();
+
+		for dfn in definitions {
+			if !self.is_builder_enabled(dfn) {
+				continue;
+			}
+
+			let builder_names = match dfn.definition.as_ref() {
+				Definition::Record(_) if !self.is_record_unit(dfn) && !self.is_record_tuple(dfn) => vec![ record_builder_name(dfn) ],
+				Definition::Enum(e) => e.cases.iter()
+					.filter(|c| !self.is_enum_case_unit(c) && !self.is_enum_case_tuple(c))
+					.map(|c| enum_case_builder_name(dfn, c))
+					.collect(),
+				_ => Vec::new(),
+			};
+
+			for builder_name in builder_names {
+				let builder_name = builder_name.to_string();
+				if !names.insert(builder_name.clone()) {
+					return Err(EmitError::DuplicateBuilderName(dfn.name.as_ref().clone(), builder_name));
+				}
+			}
+		}
+
+		Ok(())
+	}
+
 	fn is_enum_case_unit(&self, c: &EnumCase) -> bool {
 		c.annotations.iter()
 			.filter(|ann| ann.scope == "rust")
@@ -980,6 +1787,164 @@ impl <'a> ModEmitter<'a> {
 
 
 
+// Other scopes are checked by the compiler, so only the rust scope is registered here.
+fn check_annotations(model: &NobleIdlModel) -> Result<(), EmitError> {
+	let mut registry = AnnotationRegistry::new();
+	registry.register("rust", check_rust_annotation);
+
+	for problem in registry.check_model(model) {
+		if let AnnotationProblem::InvalidValue { location, message, .. } = problem {
+			return Err(EmitError::InvalidAnnotation(location, message));
+		}
+	}
+
+	Ok(())
+}
+
+fn get_extern_mapping(dfn: &DefinitionInfo) -> Option<RustAnnExternType> {
+	if !matches!(dfn.definition.as_ref(), Definition::ExternType(_)) {
+		return None;
+	}
+
+	dfn.annotations.iter()
+		.filter(|ann| ann.scope == "rust")
+		.find_map(|ann| RustAnnExternType::decode_esexpr(ann.value.clone()).ok())
+}
+
+fn get_package_derives(pkg: &PackageInfo) -> Vec<String> {
+	pkg.annotations.iter()
+		.filter(|ann| ann.scope == "rust")
+		.filter_map(|ann| RustAnnPackage::decode_esexpr(ann.value.clone()).ok())
+		.filter_map(|ann| match ann {
+			RustAnnPackage::Derive(derive) => Some(derive),
+			RustAnnPackage::Boxed => None,
+			RustAnnPackage::Serde => None,
+			RustAnnPackage::Builder => None,
+		})
+		.collect()
+}
+
+fn has_package_boxing(pkg: &PackageInfo) -> bool {
+	pkg.annotations.iter()
+		.filter(|ann| ann.scope == "rust")
+		.filter_map(|ann| RustAnnPackage::decode_esexpr(ann.value.clone()).ok())
+		.any(|ann| matches!(ann, RustAnnPackage::Boxed))
+}
+
+// The containers implement Default regardless of their element types.
+const DEFAULT_CORE_TYPES: &[&str] = &[
+	"string", "int", "nat", "bool",
+	"i8", "u8", "i16", "u16", "i32", "u32", "i64", "u64", "f32", "f64",
+	"unit", "binary", "list", "option", "optional-field", "dict",
+];
+
+fn has_field_default(field: &RecordField) -> bool {
+	let has_esexpr_default = field.esexpr_options.as_ref().is_some_and(|options| match options.kind.as_ref() {
+		EsexprRecordFieldKind::Positional(mode) => matches!(mode.as_ref(), EsexprRecordPositionalMode::Optional(_)),
+		EsexprRecordFieldKind::Keyword(_, mode) => matches!(mode.as_ref(), EsexprRecordKeywordMode::Optional(_) | EsexprRecordKeywordMode::DefaultValue(_)),
+		_ => false,
+	});
+
+	has_esexpr_default ||
+		matches!(field.field_type.as_ref(), TypeExpr::DefinedType(name, _) if name.package_name().0 == ["nobleidl", "core"] && name.name() == "optional-field")
+}
+
+fn get_simple_enum_default_case(dfn: &DefinitionInfo) -> Option<String> {
+	dfn.annotations.iter()
+		.filter(|ann| ann.scope == "rust")
+		.filter_map(|ann| RustAnnSimpleEnum::decode_esexpr(ann.value.clone()).ok())
+		.find_map(|ann| match ann {
+			RustAnnSimpleEnum::DefaultCase(name) => Some(name),
+			_ => None,
+		})
+}
+
+fn has_package_builder(pkg: &PackageInfo) -> bool {
+	pkg.annotations.iter()
+		.filter(|ann| ann.scope == "rust")
+		.filter_map(|ann| RustAnnPackage::decode_esexpr(ann.value.clone()).ok())
+		.any(|ann| matches!(ann, RustAnnPackage::Builder))
+}
+
+fn record_builder_name(dfn: &DefinitionInfo) -> syn::Ident {
+	format_ident!("{}Builder", convert_id_pascal(dfn.name.name()))
+}
+
+fn enum_case_builder_name(dfn: &DefinitionInfo, c: &EnumCase) -> syn::Ident {
+	format_ident!("{}{}Builder", convert_id_pascal(dfn.name.name()), convert_id_pascal(&c.name))
+}
+
+fn has_package_serde(pkg: &PackageInfo) -> bool {
+	pkg.annotations.iter()
+		.filter(|ann| ann.scope == "rust")
+		.filter_map(|ann| RustAnnPackage::decode_esexpr(ann.value.clone()).ok())
+		.any(|ann| matches!(ann, RustAnnPackage::Serde))
+}
+
+// Generated impls refer to the deprecated items of their own definition.
+fn allow_deprecated_attr(dfn: &DefinitionInfo) -> TokenStream {
+	if has_deprecations(dfn) {
+		quote! { #[allow(deprecated)] }
+	}
+	else {
+		quote! {}
+	}
+}
+
+fn has_deprecations(dfn: &DefinitionInfo) -> bool {
+	let fields_deprecated = |fields: &[Box<RecordField>]| fields.iter().any(|f| f.deprecated.is_some());
+
+	dfn.deprecated.is_some() ||
+		match dfn.definition.as_ref() {
+			Definition::Record(r) => fields_deprecated(&r.fields),
+			Definition::Enum(e) => e.cases.iter().any(|c| c.deprecated.is_some() || fields_deprecated(&c.fields)),
+			Definition::SimpleEnum(e) => e.cases.iter().any(|c| c.deprecated.is_some()),
+			Definition::ExternType(_) => false,
+			Definition::Interface(i) => i.methods.iter().any(|m| m.deprecated.is_some()),
+			Definition::ExceptionType(_) => false,
+		}
+}
+
+fn deprecated_attr(deprecated: Option<&Deprecation>) -> TokenStream {
+	let Some(deprecated) = deprecated else { return quote! {}; };
+
+	// Rust expects since to be a semver version, so other versions are moved into the note.
+	let (since, note) = match (deprecated.since.as_deref(), deprecated.message.as_deref()) {
+		(Some(since), message) if !is_semver(since) => {
+			let note = match message {
+				Some(message) => format!("Deprecated since {}. {}", since, message),
+				None => format!("Deprecated since {}.", since),
+			};
+			(None, Some(note))
+		},
+		(since, message) => (since, message.map(str::to_owned)),
+	};
+
+	let since = since.map(|since| quote! { since = #since });
+	let note = note.map(|note| quote! { note = #note });
+	let args = since.into_iter().chain(note).collect::<Vec<_>>();
+
+	if args.is_empty() {
+		quote! { #[deprecated] }
+	}
+	else {
+		quote! { #[deprecated(#(#args),*)] }
+	}
+}
+
+fn is_semver(version: &str) -> bool {
+	let version = version.split(['-', '+']).next().unwrap_or(version);
+	let parts = version.split('.').collect::<Vec<_>>();
+	parts.len() == 3 && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn definition_visibility(dfn: &DefinitionInfo) -> syn::Visibility {
+	match dfn.visibility {
+		DefinitionVisibility::Public => syn::Visibility::Public(Default::default()),
+		DefinitionVisibility::Internal => parse_quote! { pub(crate) },
+	}
+}
+
 fn dfn_as_type(dfn: &DefinitionInfo) -> TypeExpr {
 	TypeExpr::DefinedType(
 		dfn.name.clone(),
@@ -996,6 +1961,14 @@ fn dfn_as_type(dfn: &DefinitionInfo) -> TypeExpr {
 
 
 
+// Unchanged files are left alone so that their modification time is preserved.
+fn write_if_changed(path: &Path, content: &str) -> std::io::Result<()> {
+	match std::fs::read(path) {
+		Ok(existing) if existing == content.as_bytes() => Ok(()),
+		_ => std::fs::write(path, content),
+	}
+}
+
 fn get_package_mapping<'a>(options: &'a RustLanguageOptions) -> HashMap<PackageName, RustModule<'a>> {
 	let mut pkg_mapping = HashMap::new();
 
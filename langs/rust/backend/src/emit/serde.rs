@@ -0,0 +1,26 @@
+use noble_idl_api::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+
+// The runtime re-exports serde so that generated code does not need a direct dependency on it.
+const SERDE_CRATE: &str = "::noble_idl_runtime::serde_support::serde";
+
+pub(super) fn serde_derives() -> [TokenStream; 2] {
+	[
+		quote! { ::noble_idl_runtime::serde_support::serde::Serialize },
+		quote! { ::noble_idl_runtime::serde_support::serde::Deserialize },
+	]
+}
+
+pub(super) fn serde_crate_attr() -> TokenStream {
+	quote! { #[serde(crate = #SERDE_CRATE)] }
+}
+
+pub(super) fn serde_rename_attr(name: &str) -> TokenStream {
+	quote! { #[serde(rename = #name)] }
+}
+
+pub(super) fn serde_field_attrs(field: &RecordField, is_tuple: bool) -> TokenStream {
+	if is_tuple { quote! {} } else { serde_rename_attr(&field.name) }
+}
@@ -11,7 +11,7 @@ use syn::parse_quote;
 pub(super) trait TraitEmitter<'a>: MethodEmitter<'a> {
 	type TraitTE: TypeEmitter<'a>;
 	fn trait_name_suffix(&self) -> &'static str;
-	fn trait_vis(&self) -> syn::Visibility;
+	fn trait_vis(&self, dfn: &DefinitionInfo) -> syn::Visibility;
 	fn trait_type_emitter(&self) -> &Self::TraitTE;
 
 	fn emit_trait<'b: 'a>(&self, dfn: &'b DefinitionInfo, i: &'b InterfaceDefinition) -> Result<syn::Item, EmitError> {
@@ -31,7 +31,7 @@ pub(super) trait TraitEmitter<'a>: MethodEmitter<'a> {
 				}
 			],
 
-			vis: self.trait_vis(),
+			vis: self.trait_vis(dfn),
 			unsafety: None,
 			auto_token: None,
 			restriction: None,
@@ -1,4 +1,5 @@
-use super::{EmitError, ModEmitter, dfn_as_type};
+use super::{EmitError, ModEmitter, dfn_as_type, get_extern_mapping};
+use super::boxing::{TypeBoxing, type_argument_boxing};
 use super::ident::{convert_id_pascal, idstr};
 
 use noble_idl_api::*;
@@ -8,36 +9,27 @@ use syn::punctuated::Punctuated;
 use quote::format_ident;
 
 
-pub(super) enum TypeBoxing {
-	None,
-	Box,
-}
-
-
 pub(super) trait TypeEmitter<'a> {
 	fn mod_emitter(&self) -> &ModEmitter<'a>;
 
 
 
 	fn emit_type_expr(&self, t: &TypeExpr) -> Result<syn::Type, EmitError> {
-		let path = self.get_type_path(t)?;
-
-		let boxing = self.get_type_boxing(t);
+		self.emit_boxed_type_expr(t, TypeBoxing::None)
+	}
 
-		Ok(
-			match boxing {
-				TypeBoxing::None => {
-					syn::Type::Path(syn::TypePath {
-						qself: None,
-						path,
-					})
-				},
+	fn emit_boxed_type_expr(&self, t: &TypeExpr, boxing: TypeBoxing) -> Result<syn::Type, EmitError> {
+		let path = self.get_boxed_type_path(t, boxing)?;
 
-				TypeBoxing::Box => {
-					parse_quote! { ::std::boxed::Box<#path> }
-				},
-			}
-		)
+		if self.is_type_boxed(t, boxing) {
+			Ok(parse_quote! { ::std::boxed::Box<#path> })
+		}
+		else {
+			Ok(syn::Type::Path(syn::TypePath {
+				qself: None,
+				path,
+			}))
+		}
 	}
 
 	fn emit_return_type(&self, t: &TypeExpr, throws: Option<&TypeExpr>) -> Result<syn::Type, EmitError> {
@@ -55,29 +47,35 @@ pub(super) trait TypeEmitter<'a> {
 	}
 
 
-	fn get_type_boxing(&self, t: &TypeExpr) -> TypeBoxing {
+	fn is_type_boxed(&self, t: &TypeExpr, boxing: TypeBoxing) -> bool {
 		match t {
-			TypeExpr::DefinedType(name, _) => {
+			TypeExpr::DefinedType(name, _) =>
 				self.mod_emitter().definition_map.get(name.as_ref())
-					.map(|dfn| match dfn.definition.as_ref() {
-						Definition::Record(_) | Definition::Enum(_) | Definition::ExceptionType(_) => TypeBoxing::Box,
-						Definition::SimpleEnum(_) | Definition::ExternType(_) | Definition::Interface(_) => TypeBoxing::None,
-					})
-					.unwrap_or(TypeBoxing::None)
-			}
+					.is_some_and(|dfn| boxing.is_boxed(dfn)),
 
-			_ => TypeBoxing::None,
+			_ => false,
 		}
 	}
 
 
 	fn get_type_path(&self, t: &TypeExpr) -> Result<syn::Path, EmitError> {
+		self.get_boxed_type_path(t, TypeBoxing::None)
+	}
+
+	fn get_boxed_type_path(&self, t: &TypeExpr, boxing: TypeBoxing) -> Result<syn::Path, EmitError> {
 		Ok(match t {
 			TypeExpr::DefinedType(name, args) => {
+				let dfn = self.mod_emitter().definition_map.get(name.as_ref()).copied();
+
+				let mapping = dfn.and_then(|dfn| Some((dfn, get_extern_mapping(dfn)?)));
+
+				if let Some((dfn, RustAnnExternType::MappedTo { name, args: mapped_args })) = mapping {
+					return self.get_mapped_type_path(dfn, &name, &mapped_args, args, boxing);
+				}
 
 				let mut path = self.get_package_name_path_segments(name.package_name())?;
 
-				let arguments = self.emit_type_arguments(args)?;
+				let arguments = self.emit_boxed_type_arguments(dfn, args, boxing)?;
 
 				path.segments.push(syn::PathSegment {
 					ident: convert_id_pascal(name.name()),
@@ -95,6 +93,48 @@ pub(super) trait TypeEmitter<'a> {
 		})
 	}
 
+	// Type parameters of the extern type are replaced by the type arguments where it is used.
+	fn get_mapped_type_path(&self, dfn: &DefinitionInfo, name: &str, mapped_args: &[Box<RustMappedType>], args: &[Box<TypeExpr>], boxing: TypeBoxing) -> Result<syn::Path, EmitError> {
+		let mut path: syn::Path = syn::parse_str(name)?;
+
+		if !mapped_args.is_empty() {
+			let mapped_args = mapped_args.iter()
+				.map(|a| Ok(syn::GenericArgument::Type(self.emit_mapped_type(dfn, a, args, boxing)?)))
+				.collect::<Result<Punctuated<_, _>, EmitError>>()?;
+
+			if let Some(segment) = path.segments.last_mut() {
+				segment.arguments = syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
+					colon2_token: None,
+					lt_token: Default::default(),
+					args: mapped_args,
+					gt_token: Default::default(),
+				});
+			}
+		}
+
+		Ok(path)
+	}
+
+	fn emit_mapped_type(&self, dfn: &DefinitionInfo, t: &RustMappedType, args: &[Box<TypeExpr>], boxing: TypeBoxing) -> Result<syn::Type, EmitError> {
+		match t {
+			RustMappedType::TypeName(name) => Ok(syn::parse_str(name)?),
+
+			RustMappedType::Apply { name, args: mapped_args } => {
+				let path = self.get_mapped_type_path(dfn, name, mapped_args, args, boxing)?;
+				Ok(syn::Type::Path(syn::TypePath { qself: None, path }))
+			},
+
+			RustMappedType::TypeParameter(tp_name) => {
+				let (index, arg) = dfn.type_parameters.iter()
+					.position(|tp| tp.name() == tp_name)
+					.and_then(|index| Some((index, args.get(index)?)))
+					.ok_or_else(|| EmitError::UnknownMappedTypeParameter(dfn.name.as_ref().clone(), tp_name.clone()))?;
+
+				self.emit_boxed_type_expr(arg, type_argument_boxing(Some(dfn), index, boxing))
+			},
+		}
+	}
+
 	fn get_method_type_parameter_path(&self, name: &str) -> Result<syn::Path, EmitError> {
 		Ok(syn::Path::from(convert_id_pascal(&name)))
 	}
@@ -105,7 +145,11 @@ pub(super) trait TypeEmitter<'a> {
 
 
 	fn get_literal_type_path(&self, t: &TypeExpr) -> Result<syn::Path, EmitError> {
-		let mut path = self.get_type_path(t)?;
+		self.get_boxed_literal_type_path(t, TypeBoxing::None)
+	}
+
+	fn get_boxed_literal_type_path(&self, t: &TypeExpr, boxing: TypeBoxing) -> Result<syn::Path, EmitError> {
+		let mut path = self.get_boxed_type_path(t, boxing)?;
 		match path.segments.last_mut() {
 			Some(syn::PathSegment { arguments: syn::PathArguments::AngleBracketed(args), .. }) => {
 				args.colon2_token = Some(Default::default());
@@ -149,12 +193,17 @@ pub(super) trait TypeEmitter<'a> {
 	}
 
 	fn emit_type_arguments(&self, args: &[Box<TypeExpr>]) -> Result<syn::PathArguments, EmitError> {
+		self.emit_boxed_type_arguments(None, args, TypeBoxing::None)
+	}
+
+	fn emit_boxed_type_arguments(&self, dfn: Option<&DefinitionInfo>, args: &[Box<TypeExpr>], boxing: TypeBoxing) -> Result<syn::PathArguments, EmitError> {
 		if args.is_empty() {
 			Ok(syn::PathArguments::None)
 		}
 		else {
 			let args = args.iter()
-				.map(|a| Ok(syn::GenericArgument::Type(self.emit_type_expr(a)?)))
+				.enumerate()
+				.map(|(index, a)| Ok(syn::GenericArgument::Type(self.emit_boxed_type_expr(a, type_argument_boxing(dfn, index, boxing))?)))
 				.collect::<Result<Punctuated<_, _>, EmitError>>()?;
 
 			Ok(syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
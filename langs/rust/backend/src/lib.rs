@@ -1,27 +1,13 @@
-use std::path::PathBuf;
-
-use noble_idl_compiler::{compile, NobleIDLOptions};
-use cargo_util::load_options;
+use build_script::BuildScript;
 
 mod annotations;
+pub mod build_script;
 pub mod emit;
 pub mod cargo_util;
 
 
-pub fn compile_from_build_script() {
-    let rust_options = load_options();
-
-    let options = NobleIDLOptions {
-        library_files: rust_options.library_files.into_iter().map(PathBuf::from).collect(),
-        files: rust_options.input_files.iter().map(PathBuf::from).collect(),
-        plugin_options: rust_options.language_options,
-    };
-
-    compile(&RustPlugin, &options).unwrap();
-
-	for file in rust_options.input_files {
-		println!("cargo::rerun-if-changed={}", file);
-	}
+pub fn compile_from_build_script() -> Result<(), build_script::BuildScriptError> {
+    BuildScript::new().compile()
 }
 
 
@@ -53,6 +39,26 @@ pub struct RustLanguageOptions {
 
     #[keyword]
     pub output_dir: String,
+
+    #[keyword]
+    #[default_value = "OutputLayout::Flat"]
+    pub output_layout: OutputLayout,
+}
+
+#[derive(ESExprCodec, Debug, Clone, PartialEq)]
+pub enum OutputLayout {
+    // One file per module named after its path, for use with include_noble_idl!.
+    #[constructor = "flat"]
+    Flat,
+
+    // A module hierarchy with mod declarations that can be checked into the source tree.
+    // The output directory contains the module named root_module as mod.rs.
+    // The crate root is never generated, so root_module must not be empty.
+    #[constructor = "module-tree"]
+    ModuleTree {
+        #[keyword]
+        root_module: String,
+    },
 }
 
 
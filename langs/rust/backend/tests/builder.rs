@@ -0,0 +1,109 @@
+mod common;
+
+use noble_idl_compiler_rust::{emit::{emit_inline, EmitError}, OutputLayout};
+
+fn emit(source: &str) -> Result<String, Box<EmitError>> {
+	let model = common::compile(&[ source ]);
+	let options = common::options("builder-test", &[ ("builder.test", "") ], OutputLayout::Flat);
+
+	emit_inline(&model, &options).map(|code| code.to_string()).map_err(Box::new)
+}
+
+#[test]
+fn builders_are_opt_in() {
+	let code = emit(r#"
+		package builder.test;
+
+		import nobleidl.core;
+
+		record plain {
+			name: string;
+		}
+
+		@rust:(builder)
+		record built {
+			name: string;
+		}
+
+		@rust:(builder)
+		enum shape {
+			circle {
+				radius: int;
+			},
+		}
+	"#).unwrap();
+
+	assert!(!code.contains("PlainBuilder"));
+	assert!(code.contains("BuiltBuilder"));
+	assert!(code.contains("ShapeCircleBuilder"));
+}
+
+#[test]
+fn builders_can_be_enabled_for_a_package() {
+	let code = emit(r#"
+		@rust:(builder)
+		package builder.test;
+
+		import nobleidl.core;
+
+		record built {
+			name: string;
+		}
+	"#).unwrap();
+
+	assert!(code.contains("BuiltBuilder"));
+}
+
+#[test]
+fn duplicate_builder_names_are_reported() {
+	let result = emit(r#"
+		@rust:(builder)
+		package builder.test;
+
+		import nobleidl.core;
+
+		record foo-bar {
+			name: string;
+		}
+
+		enum foo {
+			bar {
+				name: string;
+			},
+		}
+	"#);
+
+	match result.map_err(|e| *e) {
+		Err(EmitError::DuplicateBuilderName(name, builder_name)) => {
+			assert!(name.name() == "foo" || name.name() == "foo-bar", "{:?}", name);
+			assert_eq!(builder_name, "FooBarBuilder");
+		},
+		result => panic!("Unexpected result: {:?}", result),
+	}
+}
+
+#[test]
+fn builder_names_that_clash_with_definitions_are_reported() {
+	let result = emit(r#"
+		package builder.test;
+
+		import nobleidl.core;
+
+		@rust:(builder)
+		record point {
+			x: int;
+		}
+
+		record point-builder {
+			y: int;
+		}
+	"#);
+
+	match result.map_err(|e| *e) {
+		Err(EmitError::DuplicateBuilderName(name, builder_name)) => {
+			assert_eq!(name.name(), "point");
+			assert_eq!(builder_name, "PointBuilder");
+		},
+		result => panic!("Unexpected result: {:?}", result),
+	}
+}
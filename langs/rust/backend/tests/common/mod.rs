@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+use noble_idl_api::{NobleIdlCompileModelOptions, NobleIdlModel};
+use noble_idl_compiler_rust::{CrateOptions, Crates, OutputLayout, PackageMapping, RustLanguageOptions};
+
+pub const CORE_LIBRARY: &str = include_str!("../../../../noble-idl/runtime/nobleidl-core.nidl");
+
+pub fn compile(sources: &[&str]) -> NobleIdlModel {
+	noble_idl_compiler::compile_model(NobleIdlCompileModelOptions {
+		library_files: vec![ CORE_LIBRARY.to_owned() ],
+		files: sources.iter().map(|source| (*source).to_owned()).collect(),
+	}).unwrap()
+}
+
+// Maps the packages into the crate being generated, with the core library provided by the runtime crate.
+pub fn options(crate_name: &str, package_mapping: &[(&str, &str)], output_layout: OutputLayout) -> RustLanguageOptions {
+	RustLanguageOptions {
+		crate_name: crate_name.to_owned(),
+		crates: Crates {
+			crate_options: HashMap::from([
+				(crate_name.to_owned(), CrateOptions {
+					package_mapping: PackageMapping {
+						package_mapping: package_mapping.iter()
+							.map(|(package, module)| ((*package).to_owned(), (*module).to_owned()))
+							.collect(),
+					},
+				}),
+				("noble-idl-runtime".to_owned(), CrateOptions {
+					package_mapping: PackageMapping {
+						package_mapping: HashMap::from([ ("nobleidl.core".to_owned(), String::new()) ]),
+					},
+				}),
+			]),
+		},
+		output_dir: String::new(),
+		output_layout,
+	}
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use noble_idl_api::NobleIdlGenerationRequest;
+use noble_idl_compiler_rust::{emit::{emit, EmitError}, OutputLayout, RustLanguageOptions};
+
+const SOURCES: &[&str] = &[
+	r#"
+		package module-tree.api;
+
+		import nobleidl.core;
+
+		record point {
+			x: int;
+			y: int;
+		}
+	"#,
+	r#"
+		package module-tree.api.shapes;
+
+		import module-tree.api;
+
+		record line {
+			start: point;
+			end: point;
+		}
+	"#,
+	r#"
+		package module-tree.api.shapes.curves;
+
+		import module-tree.api;
+
+		record circle {
+			center: point;
+		}
+	"#,
+];
+
+fn options(root_module: &str, mapping: &[(&str, &str)]) -> RustLanguageOptions {
+	common::options("module-tree-test", mapping, OutputLayout::ModuleTree { root_module: root_module.to_owned() })
+}
+
+fn generate(dir: &std::path::Path, options: RustLanguageOptions) -> Result<Vec<String>, Box<EmitError>> {
+	let mut request = NobleIdlGenerationRequest::new(options, common::compile(SOURCES));
+	request.output_dir = Some(dir.to_str().unwrap().to_owned());
+
+	let mut files = emit(request).map_err(Box::new)?.generated_files.into_iter()
+		.map(|file| file.strip_prefix(dir.to_str().unwrap()).unwrap().trim_start_matches('/').to_owned())
+		.collect::<Vec<_>>();
+	files.sort();
+	Ok(files)
+}
+
+const MAPPING: &[(&str, &str)] = &[
+	("module-tree.api", "api"),
+	("module-tree.api.shapes", "api::shapes"),
+	("module-tree.api.shapes.curves", "api::shapes::curves"),
+];
+
+#[test]
+fn module_tree_writes_mod_rs_and_submodules() {
+	let dir = tempfile::tempdir().unwrap();
+
+	let files = generate(dir.path(), options("api", MAPPING)).unwrap();
+	assert_eq!(files, vec![ "mod.rs", "shapes.rs", "shapes/curves.rs" ]);
+
+	let root = std::fs::read_to_string(dir.path().join("mod.rs")).unwrap();
+	assert!(root.contains("pub mod shapes;"));
+	assert!(root.contains("pub struct Point"));
+
+	let shapes = std::fs::read_to_string(dir.path().join("shapes.rs")).unwrap();
+	assert!(shapes.contains("pub mod curves;"));
+	assert!(shapes.contains("pub struct Line"));
+
+	let curves = std::fs::read_to_string(dir.path().join("shapes/curves.rs")).unwrap();
+	assert!(curves.contains("pub struct Circle"));
+
+	let manifest = std::fs::read_to_string(dir.path().join("module_tree_test.noble-idl-manifest")).unwrap();
+	assert_eq!(manifest, "mod.rs\nshapes.rs\nshapes/curves.rs\n");
+}
+
+#[test]
+fn module_tree_removes_modules_that_are_no_longer_generated() {
+	let dir = tempfile::tempdir().unwrap();
+
+	generate(dir.path(), options("api", MAPPING)).unwrap();
+
+	let mapping = &[
+		("module-tree.api", "api"),
+		("module-tree.api.shapes", "api::shapes"),
+		("module-tree.api.shapes.curves", "api::shapes"),
+	];
+	let files = generate(dir.path(), options("api", mapping)).unwrap();
+	assert_eq!(files, vec![ "mod.rs", "shapes.rs" ]);
+	assert!(!dir.path().join("shapes").exists());
+}
+
+#[test]
+fn module_tree_requires_a_root_module() {
+	let dir = tempfile::tempdir().unwrap();
+
+	let result = generate(dir.path(), options("", MAPPING));
+	assert!(matches!(result.map_err(|e| *e), Err(EmitError::EmptyRootModule)));
+	assert!(!dir.path().join("lib.rs").exists());
+}
+
+#[test]
+fn module_tree_rejects_modules_outside_the_root() {
+	let dir = tempfile::tempdir().unwrap();
+
+	let mapping = &[
+		("module-tree.api", ""),
+		("module-tree.api.shapes", "api::shapes"),
+		("module-tree.api.shapes.curves", "api::shapes::curves"),
+	];
+
+	let result = generate(dir.path(), options("api", mapping));
+	assert!(matches!(result.map_err(|e| *e), Err(EmitError::ModuleOutsideRoot(module)) if module.is_empty()));
+}
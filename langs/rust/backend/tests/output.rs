@@ -0,0 +1,113 @@
+mod common;
+
+use std::{collections::BTreeMap, path::Path, time::{Duration, SystemTime}};
+
+use noble_idl_api::NobleIdlGenerationRequest;
+use noble_idl_compiler_rust::{emit::emit, OutputLayout, RustLanguageOptions};
+
+const SHAPES: &str = r#"
+	package output.shapes;
+
+	import nobleidl.core;
+
+	record point {
+		x: int;
+		y: int;
+	}
+
+	enum shape {
+		circle {
+			center: point;
+			radius: nat;
+		},
+		polygon {
+			points: list[point];
+		},
+	}
+"#;
+
+const COLORS: &str = r#"
+	package output.colors;
+
+	import nobleidl.core;
+
+	simple enum color {
+		red,
+		green,
+		blue,
+	}
+"#;
+
+fn options() -> RustLanguageOptions {
+	common::options("output-test", &[ ("output.shapes", "shapes"), ("output.colors", "colors") ], OutputLayout::Flat)
+}
+
+fn generate(dir: &Path, sources: &[&str]) {
+	let mut request = NobleIdlGenerationRequest::new(options(), common::compile(sources));
+	request.output_dir = Some(dir.to_str().unwrap().to_owned());
+	emit(request).unwrap();
+}
+
+fn read_dir(dir: &Path) -> BTreeMap<String, Vec<u8>> {
+	std::fs::read_dir(dir).unwrap()
+		.map(|entry| {
+			let entry = entry.unwrap();
+			(entry.file_name().into_string().unwrap(), std::fs::read(entry.path()).unwrap())
+		})
+		.collect()
+}
+
+#[test]
+fn output_is_identical_across_runs() {
+	let first = tempfile::tempdir().unwrap();
+	let second = tempfile::tempdir().unwrap();
+
+	generate(first.path(), &[ SHAPES, COLORS ]);
+	generate(second.path(), &[ SHAPES, COLORS ]);
+
+	let first = read_dir(first.path());
+	assert_eq!(
+		first.keys().collect::<Vec<_>>(),
+		vec![ "output_test.noble-idl-manifest", "output_test::colors.rs", "output_test::shapes.rs" ],
+	);
+	assert_eq!(first, read_dir(second.path()));
+}
+
+#[test]
+fn unchanged_files_are_not_rewritten() {
+	let dir = tempfile::tempdir().unwrap();
+
+	generate(dir.path(), &[ SHAPES, COLORS ]);
+
+	// Backdate the outputs so that any rewrite is visible regardless of the file system's timestamp resolution.
+	let backdated = SystemTime::now() - Duration::from_secs(3600);
+	for name in read_dir(dir.path()).keys() {
+		std::fs::File::options().write(true).open(dir.path().join(name)).unwrap()
+			.set_modified(backdated).unwrap();
+	}
+
+	generate(dir.path(), &[ SHAPES, COLORS ]);
+
+	for name in read_dir(dir.path()).keys() {
+		let modified = std::fs::metadata(dir.path().join(name)).unwrap().modified().unwrap();
+		assert_eq!(modified, backdated, "{} was rewritten", name);
+	}
+}
+
+#[test]
+fn files_of_removed_packages_are_deleted() {
+	let dir = tempfile::tempdir().unwrap();
+
+	generate(dir.path(), &[ SHAPES, COLORS ]);
+	assert!(dir.path().join("output_test::colors.rs").exists());
+
+	generate(dir.path(), &[ SHAPES ]);
+
+	assert_eq!(
+		read_dir(dir.path()).keys().collect::<Vec<_>>(),
+		vec![ "output_test.noble-idl-manifest", "output_test::shapes.rs" ],
+	);
+
+	let manifest = std::fs::read_to_string(dir.path().join("output_test.noble-idl-manifest")).unwrap();
+	assert_eq!(manifest, "output_test::shapes.rs\n");
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use noble_idl_compiler_rust::{emit::{emit_inline, EmitError}, OutputLayout};
+
+fn emit(source: &str) -> Result<String, Box<EmitError>> {
+	let model = common::compile(&[ source ]);
+	let options = common::options("serde-test", &[ ("serde.test", "") ], OutputLayout::Flat);
+
+	emit_inline(&model, &options).map(|code| code.to_string()).map_err(Box::new)
+}
+
+#[test]
+fn bigints_in_core_containers_are_supported() {
+	let code = emit(r#"
+		package serde.test;
+
+		import nobleidl.core;
+
+		@rust:(serde)
+		record counts {
+			total: int;
+			sizes: list[nat];
+			limit: option[int];
+			named: dict[list[int]];
+		}
+	"#).unwrap();
+
+	assert!(!code.contains("serde_as"));
+}
+
+#[test]
+fn bigints_in_user_generic_types_are_supported() {
+	let code = emit(r#"
+		package serde.test;
+
+		import nobleidl.core;
+
+		@rust:(serde)
+		record pair[a, b] {
+			first: a;
+			second: b;
+		}
+
+		@rust:(serde)
+		record holder {
+			name: string;
+			value: pair[int, string];
+		}
+
+		@rust:(serde)
+		enum shape {
+			polygon {
+				points: list[pair[nat, nat]];
+			},
+		}
+	"#).unwrap();
+
+	assert!(code.contains("struct Holder"));
+	assert!(code.contains("enum Shape"));
+}
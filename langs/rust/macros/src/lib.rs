@@ -0,0 +1,196 @@
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
+use noble_idl_compiler::{check_files_with_library_sources, lint::LintConfiguration, model::CheckWarning, CompileModelError, Error};
+use noble_idl_compiler_rust::{emit::emit_inline, CrateOptions, Crates, OutputLayout, PackageMapping, RustLanguageOptions};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{parse::{Parse, ParseStream}, punctuated::Punctuated, LitStr, Token};
+
+
+// A copy of the core library is embedded so that the macro does not depend on files outside of its crate.
+const CORE_LIBRARY: &str = include_str!("../nobleidl-core.nidl");
+const RUNTIME_CRATE_NAME: &str = "noble-idl-runtime";
+const CORE_PACKAGE_NAME: &str = "nobleidl.core";
+
+
+/// Generates the definitions from Noble IDL files inline.
+///
+/// ```ignore
+/// noble_idl_macros::noble_idl!("idl/example.nidl", mapping = { "example.pkg" = "example" });
+/// ```
+///
+/// File paths are relative to the crate manifest directory.
+/// Packages are mapped to modules relative to the crate root, so the macro must be invoked at the crate root.
+///
+/// Warnings from checking the files are reported as deprecation warnings, so they are silenced by `#[allow(deprecated)]`.
+#[proc_macro]
+pub fn noble_idl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as NobleIdlInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+
+struct NobleIdlInput {
+    files: Vec<LitStr>,
+    mapping: HashMap<String, String>,
+}
+
+impl Parse for NobleIdlInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut files = Vec::new();
+        let mut mapping = HashMap::new();
+
+        while !input.is_empty() {
+            if input.peek(LitStr) {
+                files.push(input.parse()?);
+            }
+            else {
+                let key: syn::Ident = input.parse()?;
+                if key != "mapping" {
+                    return Err(syn::Error::new(key.span(), "Expected a file name or mapping"));
+                }
+
+                input.parse::<Token![=]>()?;
+
+                let content;
+                syn::braced!(content in input);
+
+                for entry in Punctuated::<MappingEntry, Token![,]>::parse_terminated(&content)? {
+                    mapping.insert(entry.package.value(), entry.module.value());
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+
+            input.parse::<Token![,]>()?;
+        }
+
+        if files.is_empty() {
+            return Err(input.error("Expected at least one IDL file"));
+        }
+
+        Ok(NobleIdlInput { files, mapping })
+    }
+}
+
+struct MappingEntry {
+    package: LitStr,
+    module: LitStr,
+}
+
+impl Parse for MappingEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let package = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let module = input.parse()?;
+
+        Ok(MappingEntry { package, module })
+    }
+}
+
+
+fn expand(input: NobleIdlInput) -> syn::Result<TokenStream> {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| syn::Error::new(Span::call_site(), "CARGO_MANIFEST_DIR is not set"))?;
+
+    let crate_name = std::env::var("CARGO_PKG_NAME")
+        .map_err(|_| syn::Error::new(Span::call_site(), "CARGO_PKG_NAME is not set"))?;
+
+    let mut files = Vec::new();
+    let mut paths = Vec::new();
+    for file in &input.files {
+        let path = manifest_dir.join(file.value());
+
+        std::fs::metadata(&path)
+            .map_err(|e| syn::Error::new(file.span(), format!("Could not read {}: {}", path.display(), e)))?;
+
+        let path_str = path.to_str()
+            .ok_or_else(|| syn::Error::new(file.span(), "File path is not valid UTF-8"))?
+            .to_owned();
+
+        files.push((file, path_str.clone()));
+        paths.push(path_str);
+    }
+
+    let model = check_files_with_library_sources::<CompileModelError>(
+        &[ CORE_LIBRARY ],
+        &paths.iter().map(PathBuf::from).collect::<Vec<_>>(),
+        &LintConfiguration::default(),
+    ).map_err(|e| to_syn_error(&files, e))?;
+
+    let options = RustLanguageOptions {
+        crate_name: crate_name.clone(),
+        crates: Crates {
+            crate_options: HashMap::from([
+                (crate_name, CrateOptions {
+                    package_mapping: PackageMapping {
+                        package_mapping: input.mapping,
+                    },
+                }),
+                (RUNTIME_CRATE_NAME.to_owned(), CrateOptions {
+                    package_mapping: PackageMapping {
+                        package_mapping: HashMap::from([
+                            (CORE_PACKAGE_NAME.to_owned(), "".to_owned()),
+                        ]),
+                    },
+                }),
+            ]),
+        },
+        output_dir: String::new(),
+        output_layout: OutputLayout::Flat,
+    };
+
+    let code = emit_inline(&model.model, &options)
+        .map_err(|e| syn::Error::new(Span::call_site(), format!("Error generating Rust code: {:?}", e)))?;
+
+    let warnings = model.warnings.iter().map(|warning| warning_tokens(&files, warning));
+
+    // Including the files makes the compiler rebuild the crate when they change.
+    Ok(quote! {
+        #( const _: &str = include_str!(#paths); )*
+        #( #warnings )*
+        #code
+    })
+}
+
+fn span_of(files: &[(&LitStr, String)], file: Option<&str>) -> Span {
+    file.and_then(|file| files.iter().find(|(_, path)| Path::new(path) == Path::new(file)))
+        .map_or_else(Span::call_site, |(lit, _)| lit.span())
+}
+
+// Proc macros cannot emit warnings on stable, so each warning is the note of a deprecated item that is used immediately.
+fn warning_tokens(files: &[(&LitStr, String)], warning: &CheckWarning) -> TokenStream {
+    let file = match warning {
+        CheckWarning::Lint(diagnostic) => diagnostic.location.as_ref().and_then(|location| location.file.as_deref()),
+        _ => None,
+    };
+
+    let note = warning.to_string();
+    let warning_ident = syn::Ident::new("NobleIdlWarning", span_of(files, file));
+
+    quote! {
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct NobleIdlWarning;
+            let _ = #warning_ident;
+        };
+    }
+}
+
+// Errors are reported on the file name argument of the file that contains them.
+fn to_syn_error<PE: std::fmt::Debug>(files: &[(&LitStr, String)], e: Error<PE>) -> syn::Error {
+    e.into_compile_errors()
+        .into_iter()
+        .map(|error| syn::Error::new(span_of(files, error.file.as_deref()), error))
+        .reduce(|mut a, b| {
+            a.combine(b);
+            a
+        })
+        .unwrap_or_else(|| syn::Error::new(Span::call_site(), "Unknown error"))
+}
@@ -0,0 +1,3 @@
+fn main() {
+    noble_idl_compiler_rust::build_script::BuildScript::new().run();
+}
@@ -0,0 +1,26 @@
+pub mod shapes {
+    use esexpr::ESExprCodec;
+
+    #[derive(ESExprCodec, Debug, Clone, PartialEq, Default)]
+    #[constructor = "point"]
+    pub struct Point {
+        pub x: noble_idl_runtime::Int,
+        pub y: noble_idl_runtime::Int,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn library_only_crates_are_not_generated() {
+        let output_dir = std::path::Path::new(env!("OUT_DIR")).join("noble_idl");
+
+        let generated = std::fs::read_dir(&output_dir).into_iter()
+            .flatten()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .collect::<Vec<_>>();
+
+        assert!(generated.is_empty(), "Generated {:?}", generated);
+    }
+}
@@ -0,0 +1,27 @@
+noble_idl_runtime::include_noble_idl!();
+
+
+#[cfg(test)]
+mod tests {
+	use esexpr::ESExprCodec;
+	use num_bigint::BigInt;
+	use shape_types::shapes::Point;
+
+	fn point(x: i32, y: i32) -> Point {
+		Point { x: BigInt::from(x), y: BigInt::from(y) }
+	}
+
+	#[test]
+	fn types_from_renamed_dependency() {
+		let segment = crate::Segment { start: point(1, 2), end: point(3, 4) };
+		let expr = segment.clone().encode_esexpr();
+		assert_eq!(segment, crate::Segment::decode_esexpr(expr).unwrap());
+	}
+
+	#[test]
+	fn files_in_nested_source_dirs() {
+		let label = crate::Label { text: "origin".to_owned(), at: point(0, 0) };
+		let expr = label.clone().encode_esexpr();
+		assert_eq!(label, crate::Label::decode_esexpr(expr).unwrap());
+	}
+}
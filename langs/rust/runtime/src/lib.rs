@@ -4,6 +4,9 @@ use esexpr::{ESExpr, ESExprCodec, ESExprTag};
 
 pub mod erasure;
 
+#[cfg(feature = "serde")]
+pub mod serde_support;
+
 
 include!("noble_idl_runtime.rs");
 
@@ -180,6 +183,12 @@ impl <AMapper: ValueMapper> ValueMapper for Dict_Mapper<AMapper> {
 }
 
 
+impl <A> From<DictRepr<A>> for HashMap<String, A> {
+	fn from(value: DictRepr<A>) -> Self {
+		value.values
+	}
+}
+
 impl <A> From<Box<DictRepr<A>>> for HashMap<String, A> {
 	fn from(value: Box<DictRepr<A>>) -> Self {
 		(*value).values
@@ -187,7 +196,7 @@ impl <A> From<Box<DictRepr<A>>> for HashMap<String, A> {
 }
 
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Binary(pub Vec<u8>);
 
 impl From<Binary> for Vec<u8> {
@@ -229,6 +238,22 @@ impl ESExprCodec for Binary {
 }
 
 
+/// Returned by generated builders when required fields have not been set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingFieldsError {
+	pub type_name: &'static str,
+	pub fields: Vec<&'static str>,
+}
+
+impl std::fmt::Display for MissingFieldsError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Missing required fields for {}: {}", self.type_name, self.fields.join(", "))
+	}
+}
+
+impl std::error::Error for MissingFieldsError {}
+
+
 #[macro_export]
 macro_rules! include_noble_idl {
 	() => {
@@ -9,6 +9,14 @@ pub struct DictRepr<A> {
     #[dict]
     pub values: crate::Dict<A>,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl<A: ::std::default::Default> ::std::default::Default for DictRepr<A> {
+    fn default() -> Self {
+        DictRepr {
+            values: ::std::default::Default::default(),
+        }
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -20,3 +28,11 @@ pub struct ListRepr<A> {
     #[vararg]
     pub values: crate::List<A>,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl<A: ::std::default::Default> ::std::default::Default for ListRepr<A> {
+    fn default() -> Self {
+        ListRepr {
+            values: ::std::default::Default::default(),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use base64::Engine;
+use serde::{de::{self, Visitor}, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Binary;
+
+// Generated code refers to this so that crates do not need their own dependency on it.
+// Int and nat use the serde support of num-bigint.
+pub use ::serde;
+
+
+// Human readable formats use base64 strings rather than arrays of numbers.
+impl Serialize for Binary {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		if serializer.is_human_readable() {
+			serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(&self.0))
+		}
+		else {
+			serializer.serialize_bytes(&self.0)
+		}
+	}
+}
+
+impl <'de> Deserialize<'de> for Binary {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		if deserializer.is_human_readable() {
+			deserializer.deserialize_str(BinaryVisitor)
+		}
+		else {
+			deserializer.deserialize_byte_buf(BinaryVisitor)
+		}
+	}
+}
+
+struct BinaryVisitor;
+
+impl <'de> Visitor<'de> for BinaryVisitor {
+	type Value = Binary;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("binary data")
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+		base64::engine::general_purpose::STANDARD.decode(v)
+			.map(Binary)
+			.map_err(E::custom)
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+		Ok(Binary(v.to_vec()))
+	}
+
+	fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+		Ok(Binary(v))
+	}
+
+	fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+		while let Some(b) = seq.next_element()? {
+			bytes.push(b);
+		}
+
+		Ok(Binary(bytes))
+	}
+}
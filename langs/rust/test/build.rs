@@ -1,4 +1,3 @@
 fn main() {
-    noble_idl_compiler_rust::compile_from_build_script();
+    noble_idl_compiler_rust::build_script::BuildScript::new().run();
 }
-
@@ -1,11 +1,16 @@
+// The ESExprCodec derive refers to deprecated definitions and cases without allowing it.
+#![allow(deprecated)]
+
 
 noble_idl_runtime::include_noble_idl!();
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct MyExtern;
+// Internal definitions are only used by the tests.
+#[allow(dead_code)]
+mod internal {
+	noble_idl_runtime::include_noble_idl!();
+}
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct MyExtern2<A>(A);
+noble_idl_macros::noble_idl!("macro-idl/macro-test.nidl", mapping = { "nobleidl.macrotest" = "macro_test" });
 
 
 #[cfg(test)]
@@ -68,12 +73,93 @@ mod tests {
 		assert_eq!(Some(None), v.option2_some_none);
 		assert_eq!(None, v.option2_none);
 
-		assert_eq!(Some(4), v.optional_field_some.as_ref().field);
-		assert_eq!(None, v.optional_field_none.as_ref().field);
+		assert_eq!(Some(4), v.optional_field_some.field);
+		assert_eq!(None, v.optional_field_none.field);
 
 		assert_eq!(HashMap::from([ ("a".to_owned(), 1), ("b".to_owned(), 2) ]), v.dict_value);
 		assert_eq!(HashMap::from([ ("a".to_owned(), 1), ("b".to_owned(), 2) ]), v.dict_field_value.field);
 
+		assert_eq!(crate::MySimpleEnum::B, v.simple_enum_value);
+	}
+
+	#[test]
+	fn macro_definitions() {
+		let v_expr = ESExpr::Constructor { name: "macro-point".to_string(), args: vec![ ESExpr::Int(BigInt::from(1)), ESExpr::Int(BigInt::from(2)) ], kwargs: HashMap::new() };
+		let v = crate::macro_test::MacroPoint::decode_esexpr(v_expr).unwrap();
+
+		assert_eq!(BigInt::from(1), v.x);
+		assert_eq!(BigInt::from(2), v.y);
+		assert_eq!(None, v.label);
+	}
+
+	#[test]
+	fn macro_core_library_is_up_to_date() {
+		assert_eq!(include_str!("../../../noble-idl/runtime/nobleidl-core.nidl"), include_str!("../../macros/nobleidl-core.nidl"));
+	}
+
+	#[test]
+	fn serde_round_trip() {
+		let v = crate::SerdeRecord {
+			display_name: "abc".to_owned(),
+			count: "-18446744073709551617".parse::<BigInt>().unwrap(),
+			sizes: vec![ BigUint::from(4u32) ],
+			data: noble_idl_runtime::Binary(vec![ 0xab, 0xcd ]),
+			shape: crate::SerdeShape::Polygon(BigUint::from(3u32), "triangle".to_owned()),
+			color: Some(crate::SerdeColor::DarkRed),
+			tags: HashMap::from([ ("a".to_owned(), BigInt::from(1)) ]),
+			limits: crate::SerdePair { first: BigInt::from(-5), second: BigUint::from(7u32) },
+		};
+
+		let json = serde_json::to_value(&v).unwrap();
+		assert_eq!(serde_json::json!({
+			"display-name": "abc",
+			"count": [ -1, [ 1, 0, 1 ] ],
+			"sizes": [ [ 4 ] ],
+			"data": "q80=",
+			"shape": { "polygon": [ [ 3 ], "triangle" ] },
+			"color": "dark-red",
+			"tags": { "a": [ 1, [ 1 ] ] },
+			"limits": { "first": [ -1, [ 5 ] ], "second": [ 7 ] },
+		}), json);
+
+		assert_eq!(v, serde_json::from_value(json).unwrap());
+	}
+
+	#[test]
+	fn builders() {
+		let v = crate::DefaultValuesBuilder::default().str_value("def").build().unwrap();
+		assert_eq!("def", v.str_value);
+		assert_eq!(BigInt::from(-55), v.int_minus55);
+		assert_eq!(Some(Some(4)), v.option2_some_some);
+		assert_eq!(None, v.optional_field_none.field);
+
+		let v = crate::StringIntPairBuilder::default().s("abc").i(4).build().unwrap();
+		assert_eq!(crate::StringIntPair { s: "abc".to_owned(), i: BigInt::from(4) }, v);
+
+		let e = crate::StringOrIntABuilder::default().build().unwrap_err();
+		assert_eq!("StringOrInt::A", e.type_name);
+		assert_eq!(vec![ "s" ], e.fields);
 	}
-}
 
+	#[test]
+	fn default_impls() {
+		let v = crate::DefaultValues::default();
+		assert_eq!("abc", v.str_value);
+		assert_eq!(BigUint::from(55u32), v.nat_value55);
+		assert_eq!(Some(4), v.optional_field_some.field);
+
+		assert_eq!(crate::SerdeColor::LightBlue, crate::SerdeColor::default());
+
+		let node = crate::TreeNode::default();
+		assert!(node.children.is_empty());
+		assert!(node.parent.is_none());
+	}
+
+	#[test]
+	fn internal_definitions() {
+		let point = crate::internal::InternalPoint { x: BigInt::from(1), y: BigInt::from(2) };
+		let shape = crate::internal::InternalShape::AtPoint { p: point };
+		let expr = shape.clone().encode_esexpr();
+		assert_eq!(shape, crate::internal::InternalShape::decode_esexpr(expr).unwrap());
+	}
+}
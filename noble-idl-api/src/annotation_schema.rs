@@ -0,0 +1,191 @@
+use std::{borrow::Borrow, collections::HashMap, fmt::Display};
+
+use esexpr::ESExpr;
+
+pub use crate::AnnotationTarget;
+
+use crate::{Annotation, Definition, DefinitionInfo, NobleIdlModel, PackageName, QualifiedName, RecordField, TypeParameter};
+
+impl AnnotationTarget {
+    pub fn for_definition(definition: &Definition) -> AnnotationTarget {
+        match definition {
+            Definition::Record(_) => AnnotationTarget::Record,
+            Definition::Enum(_) => AnnotationTarget::Enum,
+            Definition::SimpleEnum(_) => AnnotationTarget::SimpleEnum,
+            Definition::ExternType(_) => AnnotationTarget::ExternType,
+            Definition::Interface(_) => AnnotationTarget::Interface,
+            Definition::ExceptionType(_) => AnnotationTarget::ExceptionType,
+        }
+    }
+}
+
+impl Display for AnnotationTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AnnotationTarget::Package => "package",
+            AnnotationTarget::Record => "record",
+            AnnotationTarget::RecordField => "field",
+            AnnotationTarget::Enum => "enum",
+            AnnotationTarget::EnumCase => "enum case",
+            AnnotationTarget::SimpleEnum => "simple enum",
+            AnnotationTarget::SimpleEnumCase => "simple enum case",
+            AnnotationTarget::ExternType => "extern type",
+            AnnotationTarget::Interface => "interface",
+            AnnotationTarget::InterfaceMethod => "method",
+            AnnotationTarget::MethodParameter => "parameter",
+            AnnotationTarget::ExceptionType => "exception",
+            AnnotationTarget::TypeParameter => "type parameter",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnnotationLocation {
+    Package(PackageName),
+    Definition(QualifiedName, Vec<String>),
+}
+
+impl Display for AnnotationLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnnotationLocation::Package(name) => write!(f, "package {}", name),
+            AnnotationLocation::Definition(name, path) => {
+                write!(f, "{}", name)?;
+                for segment in path {
+                    write!(f, ".{}", segment)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnnotationProblem {
+    UnknownScope(AnnotationLocation, String),
+    InvalidValue {
+        location: AnnotationLocation,
+        scope: String,
+        message: String,
+    },
+}
+
+// Returns a description of the problem if the value is not valid for the target.
+pub type AnnotationValidator = fn(AnnotationTarget, &ESExpr) -> Result<(), String>;
+
+// The annotation scopes understood by the compiler and backends.
+// Scopes that are declared without a validator are known, but their values are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationRegistry {
+    scopes: HashMap<String, Option<AnnotationValidator>>,
+}
+
+impl AnnotationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, scope: impl Into<String>, validator: AnnotationValidator) {
+        self.scopes.insert(scope.into(), Some(validator));
+    }
+
+    pub fn declare(&mut self, scope: impl Into<String>) {
+        self.scopes.entry(scope.into()).or_insert(None);
+    }
+
+    pub fn is_known(&self, scope: &str) -> bool {
+        self.scopes.contains_key(scope)
+    }
+
+    pub fn check_annotations<A: Borrow<Annotation>>(&self, location: &AnnotationLocation, target: AnnotationTarget, annotations: &[A], problems: &mut Vec<AnnotationProblem>) {
+        for ann in annotations {
+            let ann = ann.borrow();
+            match self.scopes.get(&ann.scope) {
+                None => problems.push(AnnotationProblem::UnknownScope(location.clone(), ann.scope.clone())),
+                Some(None) => {},
+                Some(Some(validator)) => {
+                    if let Err(message) = validator(target, &ann.value) {
+                        problems.push(AnnotationProblem::InvalidValue {
+                            location: location.clone(),
+                            scope: ann.scope.clone(),
+                            message,
+                        });
+                    }
+                },
+            }
+        }
+    }
+
+    pub fn check_package<A: Borrow<Annotation>>(&self, name: &PackageName, annotations: &[A], problems: &mut Vec<AnnotationProblem>) {
+        self.check_annotations(&AnnotationLocation::Package(name.clone()), AnnotationTarget::Package, annotations, problems);
+    }
+
+    pub fn check_definition(&self, dfn: &DefinitionInfo, problems: &mut Vec<AnnotationProblem>) {
+        let location = |path: &[&str]| AnnotationLocation::Definition(
+            (*dfn.name).clone(),
+            path.iter().map(|segment| (*segment).to_owned()).collect(),
+        );
+
+        self.check_annotations(&location(&[]), AnnotationTarget::for_definition(&dfn.definition), &dfn.annotations, problems);
+        self.check_type_parameters(&location, &[], &dfn.type_parameters, problems);
+
+        match dfn.definition.as_ref() {
+            Definition::Record(rec) => self.check_fields(&location, &[], &rec.fields, problems),
+            Definition::Enum(e) => {
+                for c in &e.cases {
+                    self.check_annotations(&location(&[&c.name]), AnnotationTarget::EnumCase, &c.annotations, problems);
+                    self.check_fields(&location, &[&c.name], &c.fields, problems);
+                }
+            },
+            Definition::SimpleEnum(e) => {
+                for c in &e.cases {
+                    self.check_annotations(&location(&[&c.name]), AnnotationTarget::SimpleEnumCase, &c.annotations, problems);
+                }
+            },
+            Definition::ExternType(_) => {},
+            Definition::Interface(iface) => {
+                for method in &iface.methods {
+                    self.check_annotations(&location(&[&method.name]), AnnotationTarget::InterfaceMethod, &method.annotations, problems);
+                    self.check_type_parameters(&location, &[&method.name], &method.type_parameters, problems);
+
+                    for param in &method.parameters {
+                        self.check_annotations(&location(&[&method.name, &param.name]), AnnotationTarget::MethodParameter, &param.annotations, problems);
+                    }
+                }
+            },
+            Definition::ExceptionType(_) => {},
+        }
+    }
+
+    pub fn check_model(&self, model: &NobleIdlModel) -> Vec<AnnotationProblem> {
+        let mut problems = Vec::new();
+
+        for package in &model.packages {
+            self.check_package(&package.name, &package.annotations, &mut problems);
+        }
+
+        for dfn in &model.definitions {
+            self.check_definition(dfn, &mut problems);
+        }
+
+        problems
+    }
+
+    fn check_fields(&self, location: &impl Fn(&[&str]) -> AnnotationLocation, path: &[&str], fields: &[Box<RecordField>], problems: &mut Vec<AnnotationProblem>) {
+        for field in fields {
+            let mut field_path = path.to_vec();
+            field_path.push(&field.name);
+            self.check_annotations(&location(&field_path), AnnotationTarget::RecordField, &field.annotations, problems);
+        }
+    }
+
+    fn check_type_parameters(&self, location: &impl Fn(&[&str]) -> AnnotationLocation, path: &[&str], type_parameters: &[Box<TypeParameter>], problems: &mut Vec<AnnotationProblem>) {
+        for tp in type_parameters {
+            let mut tp_path = path.to_vec();
+            tp_path.push(tp.name());
+            self.check_annotations(&location(&tp_path), AnnotationTarget::TypeParameter, tp.annotations(), problems);
+        }
+    }
+}
@@ -1,4 +1,11 @@
-use std::{borrow::Borrow, collections::HashMap, fmt::Debug, hash::Hash};
+use std::{borrow::Borrow, collections::HashMap, fmt::{Debug, Display}, hash::Hash};
+
+use esexpr::ESExpr;
+
+pub mod annotation_schema;
+pub mod plugin_abi;
+
+pub const PROTOCOL_VERSION: u32 = 1;
 
 pub trait NobleIDLPluginExecutor {
     type LanguageOptions: Clone + Debug;
@@ -31,6 +38,34 @@ impl QualifiedName {
     }
 }
 
+impl Display for PackageName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.join("."))
+    }
+}
+
+impl Display for QualifiedName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.0.is_empty() {
+            f.write_str(&self.1)
+        }
+        else {
+            write!(f, "{}.{}", self.0, self.1)
+        }
+    }
+}
+
+impl Display for NobleIdlCompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, &self.span) {
+            (Some(file), Some(span)) => write!(f, "{}:{}:{}: {}", file, span.start_line, span.start_column, self.message),
+            (Some(file), None) => write!(f, "{}: {}", file, self.message),
+            (None, Some(span)) => write!(f, "{}:{}: {}", span.start_line, span.start_column, self.message),
+            (None, None) => f.write_str(&self.message),
+        }
+    }
+}
+
 impl TypeExpr {
 	pub fn substitute<S: AsRef<str> + Borrow<str> + Hash + Eq, TE: Borrow<TypeExpr>>(&mut self, mapping: &HashMap<S, TE>) -> bool
 	{
@@ -57,5 +92,112 @@ impl TypeParameter {
             TypeParameter::Type { annotations, .. } => annotations,
         }
     }
+
+    pub fn annotations_mut(&mut self) -> &mut Vec<Box<Annotation>> {
+        match self {
+            TypeParameter::Type { annotations, .. } => annotations,
+        }
+    }
+}
+
+impl NobleIdlPluginCapability {
+    pub fn all() -> Vec<NobleIdlPluginCapability> {
+        vec![
+            NobleIdlPluginCapability::FileContentResults,
+            NobleIdlPluginCapability::Diagnostics,
+            NobleIdlPluginCapability::MultipleRequests,
+        ]
+    }
+}
+
+impl <L> NobleIdlGenerationRequest<L> {
+    pub fn new(language_options: L, model: NobleIdlModel) -> Self {
+        NobleIdlGenerationRequest {
+            language_options,
+            model: Box::new(model),
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: NobleIdlPluginCapability::all(),
+            output_dir: None,
+        }
+    }
+
+    pub fn supports(&self, capability: NobleIdlPluginCapability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
+
+impl NobleIdlGenerationResult {
+    pub fn new(generated_files: Vec<String>) -> Self {
+        NobleIdlGenerationResult {
+            generated_files,
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: Vec::new(),
+            file_contents: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn check_capabilities(&self, offered: &[NobleIdlPluginCapability]) -> Result<(), ProtocolError> {
+        let used = self.capabilities.iter().copied()
+            .chain((!self.file_contents.is_empty()).then_some(NobleIdlPluginCapability::FileContentResults))
+            .chain((!self.diagnostics.is_empty()).then_some(NobleIdlPluginCapability::Diagnostics));
+
+        for capability in used {
+            if !offered.contains(&capability) {
+                return Err(ProtocolError::UnsupportedCapability(capability));
+            }
+        }
+
+        Ok(())
+    }
 }
 
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtocolError {
+    IncompatibleVersion {
+        expected: u32,
+        actual: u32,
+    },
+    UnsupportedCapability(NobleIdlPluginCapability),
+}
+
+impl Display for ProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtocolError::IncompatibleVersion { expected, actual: 0 } =>
+                write!(f, "Peer does not declare a protocol version (expected version {}); it was likely built against an older compiler-api.nidl", expected),
+            ProtocolError::IncompatibleVersion { expected, actual } =>
+                write!(f, "Incompatible plugin protocol version {} (expected version {})", actual, expected),
+            ProtocolError::UnsupportedCapability(capability) =>
+                write!(f, "Plugin used capability {:?} which was not offered by the compiler", capability),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+// Reads the protocol version from an encoded request or result without decoding the rest of it,
+// so that a version mismatch is reported instead of a decode error.
+pub fn peek_protocol_version(expr: &ESExpr) -> u32 {
+    match expr {
+        ESExpr::Constructor { kwargs, .. } => match kwargs.get("protocol-version") {
+            Some(ESExpr::Int(i)) => u32::try_from(i).unwrap_or(u32::MAX),
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+pub fn check_protocol_version(expr: &ESExpr) -> Result<(), ProtocolError> {
+    let actual = peek_protocol_version(expr);
+    if actual == PROTOCOL_VERSION {
+        Ok(())
+    }
+    else {
+        Err(ProtocolError::IncompatibleVersion {
+            expected: PROTOCOL_VERSION,
+            actual,
+        })
+    }
+}
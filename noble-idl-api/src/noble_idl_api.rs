@@ -8,6 +8,65 @@
 pub struct Annotation {
     pub scope: ::noble_idl_runtime::String,
     pub value: ::noble_idl_runtime::Esexpr,
+    #[keyword = "decoded-value"]
+    #[optional]
+    pub decoded_value: ::noble_idl_runtime::OptionalField<
+        ::std::boxed::Box<crate::EsexprDecodedValue>,
+    >,
+}
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[constructor = "annotation-scope-info"]
+pub struct AnnotationScopeInfo {
+    #[keyword = "scope"]
+    pub scope: ::noble_idl_runtime::String,
+    #[keyword = "target"]
+    pub target: crate::AnnotationTarget,
+    #[keyword = "value-type"]
+    pub value_type: ::std::boxed::Box<crate::TypeExpr>,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::marker::Copy,
+    ::std::cmp::PartialEq,
+    std::hash::Hash,
+    std::cmp::Eq,
+    ::esexpr::ESExprCodec
+)]
+#[simple_enum]
+pub enum AnnotationTarget {
+    #[constructor = "package"]
+    Package,
+    #[constructor = "record"]
+    Record,
+    #[constructor = "record-field"]
+    RecordField,
+    #[constructor = "enum"]
+    Enum,
+    #[constructor = "enum-case"]
+    EnumCase,
+    #[constructor = "simple-enum"]
+    SimpleEnum,
+    #[constructor = "simple-enum-case"]
+    SimpleEnumCase,
+    #[constructor = "extern-type"]
+    ExternType,
+    #[constructor = "interface"]
+    Interface,
+    #[constructor = "interface-method"]
+    InterfaceMethod,
+    #[constructor = "method-parameter"]
+    MethodParameter,
+    #[constructor = "exception-type"]
+    ExceptionType,
+    #[constructor = "type-parameter"]
+    TypeParameter,
 }
 #[allow(non_camel_case_types)]
 #[derive(
@@ -50,6 +109,63 @@ pub struct DefinitionInfo {
     pub annotations: ::noble_idl_runtime::List<::std::boxed::Box<crate::Annotation>>,
     #[keyword = "is-library"]
     pub is_library: ::noble_idl_runtime::Bool,
+    #[keyword = "visibility"]
+    #[default_value = "crate :: DefinitionVisibility :: Public"]
+    pub visibility: crate::DefinitionVisibility,
+    #[keyword = "deprecated"]
+    #[optional]
+    pub deprecated: ::noble_idl_runtime::OptionalField<
+        ::std::boxed::Box<crate::Deprecation>,
+    >,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::marker::Copy,
+    ::std::cmp::PartialEq,
+    std::hash::Hash,
+    std::cmp::Eq,
+    ::esexpr::ESExprCodec
+)]
+#[simple_enum]
+pub enum DefinitionVisibility {
+    #[constructor = "public"]
+    Public,
+    #[constructor = "internal"]
+    Internal,
+}
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[constructor = "deprecation"]
+pub struct Deprecation {
+    #[keyword = "message"]
+    #[optional]
+    pub message: ::noble_idl_runtime::OptionalField<::noble_idl_runtime::String>,
+    #[keyword = "since"]
+    #[optional]
+    pub since: ::noble_idl_runtime::OptionalField<::noble_idl_runtime::String>,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for Deprecation {
+    fn default() -> Self {
+        Deprecation {
+            message: <::noble_idl_runtime::OptionalField<
+                ::noble_idl_runtime::String,
+            > as ::std::convert::From<
+                ::std::option::Option<::noble_idl_runtime::String>,
+            >>::from(::std::option::Option::None),
+            since: <::noble_idl_runtime::OptionalField<
+                ::noble_idl_runtime::String,
+            > as ::std::convert::From<
+                ::std::option::Option<::noble_idl_runtime::String>,
+            >>::from(::std::option::Option::None),
+        }
+    }
 }
 #[derive(
     ::std::fmt::Debug,
@@ -69,6 +185,31 @@ pub struct EnumCase {
     >,
     #[keyword = "annotations"]
     pub annotations: ::noble_idl_runtime::List<::std::boxed::Box<crate::Annotation>>,
+    #[keyword = "deprecated"]
+    #[optional]
+    pub deprecated: ::noble_idl_runtime::OptionalField<
+        ::std::boxed::Box<crate::Deprecation>,
+    >,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for EnumCase {
+    fn default() -> Self {
+        EnumCase {
+            name: ::std::default::Default::default(),
+            fields: ::std::default::Default::default(),
+            esexpr_options: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::EsexprEnumCaseOptions>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::EsexprEnumCaseOptions>>,
+            >>::from(::std::option::Option::None),
+            annotations: ::std::default::Default::default(),
+            deprecated: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::Deprecation>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::Deprecation>>,
+            >>::from(::std::option::Option::None),
+        }
+    }
 }
 #[derive(
     ::std::fmt::Debug,
@@ -86,6 +227,19 @@ pub struct EnumDefinition {
         ::std::boxed::Box<crate::EsexprEnumOptions>,
     >,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for EnumDefinition {
+    fn default() -> Self {
+        EnumDefinition {
+            cases: ::std::default::Default::default(),
+            esexpr_options: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::EsexprEnumOptions>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::EsexprEnumOptions>>,
+            >>::from(::std::option::Option::None),
+        }
+    }
+}
 #[allow(non_camel_case_types)]
 #[derive(
     ::std::fmt::Debug,
@@ -96,6 +250,8 @@ pub struct EnumDefinition {
 pub enum EsexprAnnEnum {
     #[constructor = "derive-codec"]
     DeriveCodec,
+    #[constructor = "no-derive-codec"]
+    NoDeriveCodec,
 }
 #[allow(non_camel_case_types)]
 #[derive(
@@ -136,9 +292,22 @@ pub enum EsexprAnnExternType {
     ::std::cmp::PartialEq,
     ::esexpr::ESExprCodec
 )]
+pub enum EsexprAnnPackage {
+    #[constructor = "derive-codec"]
+    DeriveCodec,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
 pub enum EsexprAnnRecord {
     #[constructor = "derive-codec"]
     DeriveCodec,
+    #[constructor = "no-derive-codec"]
+    NoDeriveCodec,
     #[constructor = "constructor"]
     Constructor(::noble_idl_runtime::String),
 }
@@ -171,6 +340,8 @@ pub enum EsexprAnnRecordField {
 pub enum EsexprAnnSimpleEnum {
     #[constructor = "derive-codec"]
     DeriveCodec,
+    #[constructor = "no-derive-codec"]
+    NoDeriveCodec,
 }
 #[allow(non_camel_case_types)]
 #[derive(
@@ -283,6 +454,11 @@ pub enum EsexprDecodedValue {
         #[optional]
         max_level: ::noble_idl_runtime::OptionalField<::noble_idl_runtime::Nat>,
     },
+    #[constructor = "from-esexpr"]
+    FromEsexpr {
+        t: ::std::boxed::Box<crate::TypeExpr>,
+        value: ::noble_idl_runtime::Esexpr,
+    },
 }
 #[derive(
     ::std::fmt::Debug,
@@ -315,6 +491,12 @@ pub enum EsexprEnumCaseType {
 )]
 #[constructor = "enum-options"]
 pub struct EsexprEnumOptions {}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for EsexprEnumOptions {
+    fn default() -> Self {
+        EsexprEnumOptions {}
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -362,6 +544,57 @@ pub struct EsexprExternTypeLiterals {
     #[default_value = "< :: noble_idl_runtime :: Bool as :: std :: convert :: From < :: std :: primitive :: bool > > :: from (false)"]
     pub build_literal_from_adjust_null: ::noble_idl_runtime::Bool,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for EsexprExternTypeLiterals {
+    fn default() -> Self {
+        EsexprExternTypeLiterals {
+            allow_bool: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+            allow_int: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+            min_int: <::noble_idl_runtime::OptionalField<
+                ::noble_idl_runtime::Int,
+            > as ::std::convert::From<
+                ::std::option::Option<::noble_idl_runtime::Int>,
+            >>::from(::std::option::Option::None),
+            max_int: <::noble_idl_runtime::OptionalField<
+                ::noble_idl_runtime::Int,
+            > as ::std::convert::From<
+                ::std::option::Option<::noble_idl_runtime::Int>,
+            >>::from(::std::option::Option::None),
+            allow_str: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+            allow_binary: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+            allow_float32: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+            allow_float64: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+            allow_null: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+            null_max_level: <::noble_idl_runtime::OptionalField<
+                ::noble_idl_runtime::Nat,
+            > as ::std::convert::From<
+                ::std::option::Option<::noble_idl_runtime::Nat>,
+            >>::from(::std::option::Option::None),
+            build_literal_from: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::TypeExpr>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::TypeExpr>>,
+            >>::from(::std::option::Option::None),
+            build_literal_from_adjust_null: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+        }
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -391,6 +624,32 @@ pub struct EsexprExternTypeOptions {
     #[keyword = "literals"]
     pub literals: ::std::boxed::Box<crate::EsexprExternTypeLiterals>,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for EsexprExternTypeOptions {
+    fn default() -> Self {
+        EsexprExternTypeOptions {
+            allow_value: <::noble_idl_runtime::Bool as ::std::convert::From<
+                ::std::primitive::bool,
+            >>::from(false),
+            allow_optional: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::TypeExpr>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::TypeExpr>>,
+            >>::from(::std::option::Option::None),
+            allow_vararg: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::TypeExpr>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::TypeExpr>>,
+            >>::from(::std::option::Option::None),
+            allow_dict: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::TypeExpr>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::TypeExpr>>,
+            >>::from(::std::option::Option::None),
+            literals: ::std::default::Default::default(),
+        }
+    }
+}
 #[allow(non_camel_case_types)]
 #[derive(
     ::std::fmt::Debug,
@@ -447,6 +706,14 @@ pub struct EsexprRecordOptions {
     #[keyword = "constructor"]
     pub constructor: ::noble_idl_runtime::String,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for EsexprRecordOptions {
+    fn default() -> Self {
+        EsexprRecordOptions {
+            constructor: ::std::default::Default::default(),
+        }
+    }
+}
 #[allow(non_camel_case_types)]
 #[derive(
     ::std::fmt::Debug,
@@ -470,6 +737,14 @@ pub enum EsexprRecordPositionalMode {
 pub struct EsexprSimpleEnumCaseOptions {
     pub name: ::noble_idl_runtime::String,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for EsexprSimpleEnumCaseOptions {
+    fn default() -> Self {
+        EsexprSimpleEnumCaseOptions {
+            name: ::std::default::Default::default(),
+        }
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -478,6 +753,12 @@ pub struct EsexprSimpleEnumCaseOptions {
 )]
 #[constructor = "simple-enum-options"]
 pub struct EsexprSimpleEnumOptions {}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for EsexprSimpleEnumOptions {
+    fn default() -> Self {
+        EsexprSimpleEnumOptions {}
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -502,6 +783,18 @@ pub struct ExternTypeDefinition {
         ::std::boxed::Box<crate::EsexprExternTypeOptions>,
     >,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for ExternTypeDefinition {
+    fn default() -> Self {
+        ExternTypeDefinition {
+            esexpr_options: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::EsexprExternTypeOptions>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::EsexprExternTypeOptions>>,
+            >>::from(::std::option::Option::None),
+        }
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -513,6 +806,14 @@ pub struct InterfaceDefinition {
     #[vararg]
     pub methods: ::noble_idl_runtime::List<::std::boxed::Box<crate::InterfaceMethod>>,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for InterfaceDefinition {
+    fn default() -> Self {
+        InterfaceDefinition {
+            methods: ::std::default::Default::default(),
+        }
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -538,6 +839,11 @@ pub struct InterfaceMethod {
     pub throws: ::noble_idl_runtime::OptionalField<::std::boxed::Box<crate::TypeExpr>>,
     #[keyword = "annotations"]
     pub annotations: ::noble_idl_runtime::List<::std::boxed::Box<crate::Annotation>>,
+    #[keyword = "deprecated"]
+    #[optional]
+    pub deprecated: ::noble_idl_runtime::OptionalField<
+        ::std::boxed::Box<crate::Deprecation>,
+    >,
 }
 #[derive(
     ::std::fmt::Debug,
@@ -552,6 +858,71 @@ pub struct InterfaceMethodParameter {
     #[keyword = "annotations"]
     pub annotations: ::noble_idl_runtime::List<::std::boxed::Box<crate::Annotation>>,
 }
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum NobleIdlCheckResult {
+    #[constructor = "success"]
+    Success {
+        #[keyword = "model"]
+        #[optional]
+        model: ::noble_idl_runtime::OptionalField<
+            ::std::boxed::Box<crate::NobleIdlModel>,
+        >,
+        #[keyword = "warnings"]
+        #[default_value = "< :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: NobleIdlCompileError > > as :: std :: convert :: From < :: std :: boxed :: Box < :: noble_idl_runtime :: ListRepr < :: std :: boxed :: Box < crate :: NobleIdlCompileError > > > > > :: from (:: std :: boxed :: Box :: new (:: noble_idl_runtime :: ListRepr :: < :: std :: boxed :: Box < crate :: NobleIdlCompileError > > { values : < :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: NobleIdlCompileError > > as :: std :: convert :: From < std :: vec :: Vec < :: std :: boxed :: Box < crate :: NobleIdlCompileError > > > > :: from (:: std :: vec ! []) }))"]
+        warnings: ::noble_idl_runtime::List<
+            ::std::boxed::Box<crate::NobleIdlCompileError>,
+        >,
+    },
+    #[constructor = "failure"]
+    Failure {
+        #[vararg]
+        errors: ::noble_idl_runtime::List<::std::boxed::Box<crate::NobleIdlCompileError>>,
+    },
+}
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[constructor = "noble-idl-compile-error"]
+pub struct NobleIdlCompileError {
+    pub kind: crate::NobleIdlCompileErrorKind,
+    pub message: ::noble_idl_runtime::String,
+    #[keyword = "file"]
+    #[optional]
+    pub file: ::noble_idl_runtime::OptionalField<::noble_idl_runtime::String>,
+    #[keyword = "span"]
+    #[optional]
+    pub span: ::noble_idl_runtime::OptionalField<
+        ::std::boxed::Box<crate::NobleIdlSourceSpan>,
+    >,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::marker::Copy,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[simple_enum]
+pub enum NobleIdlCompileErrorKind {
+    #[constructor = "options-error"]
+    OptionsError,
+    #[constructor = "io-error"]
+    IoError,
+    #[constructor = "parse-error"]
+    ParseError,
+    #[constructor = "check-error"]
+    CheckError,
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -565,6 +936,15 @@ pub struct NobleIdlCompileModelOptions {
     #[keyword = "files"]
     pub files: ::noble_idl_runtime::List<::noble_idl_runtime::String>,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for NobleIdlCompileModelOptions {
+    fn default() -> Self {
+        NobleIdlCompileModelOptions {
+            library_files: ::std::default::Default::default(),
+            files: ::std::default::Default::default(),
+        }
+    }
+}
 #[allow(non_camel_case_types)]
 #[derive(
     ::std::fmt::Debug,
@@ -578,6 +958,77 @@ pub enum NobleIdlCompileModelResult {
     #[constructor = "failure"]
     Failure { #[vararg] errors: ::noble_idl_runtime::List<::noble_idl_runtime::String> },
 }
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::marker::Copy,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[simple_enum]
+pub enum NobleIdlDiagnosticSeverity {
+    #[constructor = "error"]
+    Error,
+    #[constructor = "warning"]
+    Warning,
+    #[constructor = "note"]
+    Note,
+}
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[constructor = "format-options"]
+pub struct NobleIdlFormatOptions {
+    pub source: ::noble_idl_runtime::String,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for NobleIdlFormatOptions {
+    fn default() -> Self {
+        NobleIdlFormatOptions {
+            source: ::std::default::Default::default(),
+        }
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum NobleIdlFormatResult {
+    #[constructor = "success"]
+    Success { formatted: ::noble_idl_runtime::String },
+    #[constructor = "failure"]
+    Failure {
+        #[vararg]
+        errors: ::noble_idl_runtime::List<::std::boxed::Box<crate::NobleIdlCompileError>>,
+    },
+}
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[constructor = "noble-idl-generated-file"]
+pub struct NobleIdlGeneratedFile {
+    pub path: ::noble_idl_runtime::String,
+    pub content: ::noble_idl_runtime::String,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for NobleIdlGeneratedFile {
+    fn default() -> Self {
+        NobleIdlGeneratedFile {
+            path: ::std::default::Default::default(),
+            content: ::std::default::Default::default(),
+        }
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -590,6 +1041,50 @@ pub struct NobleIdlGenerationRequest<L> {
     pub language_options: L,
     #[keyword = "model"]
     pub model: ::std::boxed::Box<crate::NobleIdlModel>,
+    #[keyword = "protocol-version"]
+    #[default_value = "< :: noble_idl_runtime :: U32 as std :: convert :: From < :: std :: primitive :: u32 > > :: from (0u32)"]
+    pub protocol_version: ::noble_idl_runtime::U32,
+    #[keyword = "capabilities"]
+    #[default_value = "< :: noble_idl_runtime :: List < crate :: NobleIdlPluginCapability > as :: std :: convert :: From < :: std :: boxed :: Box < :: noble_idl_runtime :: ListRepr < crate :: NobleIdlPluginCapability > > > > :: from (:: std :: boxed :: Box :: new (:: noble_idl_runtime :: ListRepr :: < crate :: NobleIdlPluginCapability > { values : < :: noble_idl_runtime :: List < crate :: NobleIdlPluginCapability > as :: std :: convert :: From < std :: vec :: Vec < crate :: NobleIdlPluginCapability > > > :: from (:: std :: vec ! []) }))"]
+    pub capabilities: ::noble_idl_runtime::List<crate::NobleIdlPluginCapability>,
+    #[keyword = "output-dir"]
+    #[optional]
+    pub output_dir: ::noble_idl_runtime::OptionalField<::noble_idl_runtime::String>,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl<L: ::std::default::Default> ::std::default::Default
+for NobleIdlGenerationRequest<L> {
+    fn default() -> Self {
+        NobleIdlGenerationRequest {
+            language_options: ::std::default::Default::default(),
+            model: ::std::default::Default::default(),
+            protocol_version: <::noble_idl_runtime::U32 as std::convert::From<
+                ::std::primitive::u32,
+            >>::from(0u32),
+            capabilities: <::noble_idl_runtime::List<
+                crate::NobleIdlPluginCapability,
+            > as ::std::convert::From<
+                ::std::boxed::Box<
+                    ::noble_idl_runtime::ListRepr<crate::NobleIdlPluginCapability>,
+                >,
+            >>::from(
+                ::std::boxed::Box::new(::noble_idl_runtime::ListRepr::<
+                    crate::NobleIdlPluginCapability,
+                > {
+                    values: <::noble_idl_runtime::List<
+                        crate::NobleIdlPluginCapability,
+                    > as ::std::convert::From<
+                        std::vec::Vec<crate::NobleIdlPluginCapability>,
+                    >>::from(::std::vec![]),
+                }),
+            ),
+            output_dir: <::noble_idl_runtime::OptionalField<
+                ::noble_idl_runtime::String,
+            > as ::std::convert::From<
+                ::std::option::Option<::noble_idl_runtime::String>,
+            >>::from(::std::option::Option::None),
+        }
+    }
 }
 #[derive(
     ::std::fmt::Debug,
@@ -601,6 +1096,88 @@ pub struct NobleIdlGenerationRequest<L> {
 pub struct NobleIdlGenerationResult {
     #[keyword = "generated-files"]
     pub generated_files: ::noble_idl_runtime::List<::noble_idl_runtime::String>,
+    #[keyword = "protocol-version"]
+    #[default_value = "< :: noble_idl_runtime :: U32 as std :: convert :: From < :: std :: primitive :: u32 > > :: from (0u32)"]
+    pub protocol_version: ::noble_idl_runtime::U32,
+    #[keyword = "capabilities"]
+    #[default_value = "< :: noble_idl_runtime :: List < crate :: NobleIdlPluginCapability > as :: std :: convert :: From < :: std :: boxed :: Box < :: noble_idl_runtime :: ListRepr < crate :: NobleIdlPluginCapability > > > > :: from (:: std :: boxed :: Box :: new (:: noble_idl_runtime :: ListRepr :: < crate :: NobleIdlPluginCapability > { values : < :: noble_idl_runtime :: List < crate :: NobleIdlPluginCapability > as :: std :: convert :: From < std :: vec :: Vec < crate :: NobleIdlPluginCapability > > > :: from (:: std :: vec ! []) }))"]
+    pub capabilities: ::noble_idl_runtime::List<crate::NobleIdlPluginCapability>,
+    #[keyword = "file-contents"]
+    #[default_value = "< :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: NobleIdlGeneratedFile > > as :: std :: convert :: From < :: std :: boxed :: Box < :: noble_idl_runtime :: ListRepr < :: std :: boxed :: Box < crate :: NobleIdlGeneratedFile > > > > > :: from (:: std :: boxed :: Box :: new (:: noble_idl_runtime :: ListRepr :: < :: std :: boxed :: Box < crate :: NobleIdlGeneratedFile > > { values : < :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: NobleIdlGeneratedFile > > as :: std :: convert :: From < std :: vec :: Vec < :: std :: boxed :: Box < crate :: NobleIdlGeneratedFile > > > > :: from (:: std :: vec ! []) }))"]
+    pub file_contents: ::noble_idl_runtime::List<
+        ::std::boxed::Box<crate::NobleIdlGeneratedFile>,
+    >,
+    #[keyword = "diagnostics"]
+    #[default_value = "< :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: NobleIdlPluginDiagnostic > > as :: std :: convert :: From < :: std :: boxed :: Box < :: noble_idl_runtime :: ListRepr < :: std :: boxed :: Box < crate :: NobleIdlPluginDiagnostic > > > > > :: from (:: std :: boxed :: Box :: new (:: noble_idl_runtime :: ListRepr :: < :: std :: boxed :: Box < crate :: NobleIdlPluginDiagnostic > > { values : < :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: NobleIdlPluginDiagnostic > > as :: std :: convert :: From < std :: vec :: Vec < :: std :: boxed :: Box < crate :: NobleIdlPluginDiagnostic > > > > :: from (:: std :: vec ! []) }))"]
+    pub diagnostics: ::noble_idl_runtime::List<
+        ::std::boxed::Box<crate::NobleIdlPluginDiagnostic>,
+    >,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for NobleIdlGenerationResult {
+    fn default() -> Self {
+        NobleIdlGenerationResult {
+            generated_files: ::std::default::Default::default(),
+            protocol_version: <::noble_idl_runtime::U32 as std::convert::From<
+                ::std::primitive::u32,
+            >>::from(0u32),
+            capabilities: <::noble_idl_runtime::List<
+                crate::NobleIdlPluginCapability,
+            > as ::std::convert::From<
+                ::std::boxed::Box<
+                    ::noble_idl_runtime::ListRepr<crate::NobleIdlPluginCapability>,
+                >,
+            >>::from(
+                ::std::boxed::Box::new(::noble_idl_runtime::ListRepr::<
+                    crate::NobleIdlPluginCapability,
+                > {
+                    values: <::noble_idl_runtime::List<
+                        crate::NobleIdlPluginCapability,
+                    > as ::std::convert::From<
+                        std::vec::Vec<crate::NobleIdlPluginCapability>,
+                    >>::from(::std::vec![]),
+                }),
+            ),
+            file_contents: <::noble_idl_runtime::List<
+                ::std::boxed::Box<crate::NobleIdlGeneratedFile>,
+            > as ::std::convert::From<
+                ::std::boxed::Box<
+                    ::noble_idl_runtime::ListRepr<
+                        ::std::boxed::Box<crate::NobleIdlGeneratedFile>,
+                    >,
+                >,
+            >>::from(
+                ::std::boxed::Box::new(::noble_idl_runtime::ListRepr::<
+                    ::std::boxed::Box<crate::NobleIdlGeneratedFile>,
+                > {
+                    values: <::noble_idl_runtime::List<
+                        ::std::boxed::Box<crate::NobleIdlGeneratedFile>,
+                    > as ::std::convert::From<
+                        std::vec::Vec<::std::boxed::Box<crate::NobleIdlGeneratedFile>>,
+                    >>::from(::std::vec![]),
+                }),
+            ),
+            diagnostics: <::noble_idl_runtime::List<
+                ::std::boxed::Box<crate::NobleIdlPluginDiagnostic>,
+            > as ::std::convert::From<
+                ::std::boxed::Box<
+                    ::noble_idl_runtime::ListRepr<
+                        ::std::boxed::Box<crate::NobleIdlPluginDiagnostic>,
+                    >,
+                >,
+            >>::from(
+                ::std::boxed::Box::new(::noble_idl_runtime::ListRepr::<
+                    ::std::boxed::Box<crate::NobleIdlPluginDiagnostic>,
+                > {
+                    values: <::noble_idl_runtime::List<
+                        ::std::boxed::Box<crate::NobleIdlPluginDiagnostic>,
+                    > as ::std::convert::From<
+                        std::vec::Vec<::std::boxed::Box<crate::NobleIdlPluginDiagnostic>>,
+                    >>::from(::std::vec![]),
+                }),
+            ),
+        }
+    }
 }
 #[derive(
     ::std::fmt::Debug,
@@ -612,6 +1189,138 @@ pub struct NobleIdlGenerationResult {
 pub struct NobleIdlModel {
     #[keyword = "definitions"]
     pub definitions: ::noble_idl_runtime::List<::std::boxed::Box<crate::DefinitionInfo>>,
+    #[keyword = "packages"]
+    #[default_value = "< :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: PackageInfo > > as :: std :: convert :: From < :: std :: boxed :: Box < :: noble_idl_runtime :: ListRepr < :: std :: boxed :: Box < crate :: PackageInfo > > > > > :: from (:: std :: boxed :: Box :: new (:: noble_idl_runtime :: ListRepr :: < :: std :: boxed :: Box < crate :: PackageInfo > > { values : < :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: PackageInfo > > as :: std :: convert :: From < std :: vec :: Vec < :: std :: boxed :: Box < crate :: PackageInfo > > > > :: from (:: std :: vec ! []) }))"]
+    pub packages: ::noble_idl_runtime::List<::std::boxed::Box<crate::PackageInfo>>,
+    #[keyword = "annotation-scopes"]
+    #[default_value = "< :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: AnnotationScopeInfo > > as :: std :: convert :: From < :: std :: boxed :: Box < :: noble_idl_runtime :: ListRepr < :: std :: boxed :: Box < crate :: AnnotationScopeInfo > > > > > :: from (:: std :: boxed :: Box :: new (:: noble_idl_runtime :: ListRepr :: < :: std :: boxed :: Box < crate :: AnnotationScopeInfo > > { values : < :: noble_idl_runtime :: List < :: std :: boxed :: Box < crate :: AnnotationScopeInfo > > as :: std :: convert :: From < std :: vec :: Vec < :: std :: boxed :: Box < crate :: AnnotationScopeInfo > > > > :: from (:: std :: vec ! []) }))"]
+    pub annotation_scopes: ::noble_idl_runtime::List<
+        ::std::boxed::Box<crate::AnnotationScopeInfo>,
+    >,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for NobleIdlModel {
+    fn default() -> Self {
+        NobleIdlModel {
+            definitions: ::std::default::Default::default(),
+            packages: <::noble_idl_runtime::List<
+                ::std::boxed::Box<crate::PackageInfo>,
+            > as ::std::convert::From<
+                ::std::boxed::Box<
+                    ::noble_idl_runtime::ListRepr<::std::boxed::Box<crate::PackageInfo>>,
+                >,
+            >>::from(
+                ::std::boxed::Box::new(::noble_idl_runtime::ListRepr::<
+                    ::std::boxed::Box<crate::PackageInfo>,
+                > {
+                    values: <::noble_idl_runtime::List<
+                        ::std::boxed::Box<crate::PackageInfo>,
+                    > as ::std::convert::From<
+                        std::vec::Vec<::std::boxed::Box<crate::PackageInfo>>,
+                    >>::from(::std::vec![]),
+                }),
+            ),
+            annotation_scopes: <::noble_idl_runtime::List<
+                ::std::boxed::Box<crate::AnnotationScopeInfo>,
+            > as ::std::convert::From<
+                ::std::boxed::Box<
+                    ::noble_idl_runtime::ListRepr<
+                        ::std::boxed::Box<crate::AnnotationScopeInfo>,
+                    >,
+                >,
+            >>::from(
+                ::std::boxed::Box::new(::noble_idl_runtime::ListRepr::<
+                    ::std::boxed::Box<crate::AnnotationScopeInfo>,
+                > {
+                    values: <::noble_idl_runtime::List<
+                        ::std::boxed::Box<crate::AnnotationScopeInfo>,
+                    > as ::std::convert::From<
+                        std::vec::Vec<::std::boxed::Box<crate::AnnotationScopeInfo>>,
+                    >>::from(::std::vec![]),
+                }),
+            ),
+        }
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::marker::Copy,
+    ::std::cmp::PartialEq,
+    std::hash::Hash,
+    std::cmp::Eq,
+    ::esexpr::ESExprCodec
+)]
+#[simple_enum]
+pub enum NobleIdlPluginCapability {
+    #[constructor = "file-content-results"]
+    FileContentResults,
+    #[constructor = "diagnostics"]
+    Diagnostics,
+    #[constructor = "multiple-requests"]
+    MultipleRequests,
+}
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[constructor = "noble-idl-plugin-diagnostic"]
+pub struct NobleIdlPluginDiagnostic {
+    pub severity: crate::NobleIdlDiagnosticSeverity,
+    pub message: ::noble_idl_runtime::String,
+}
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[constructor = "noble-idl-source-span"]
+pub struct NobleIdlSourceSpan {
+    #[keyword = "start-line"]
+    pub start_line: ::noble_idl_runtime::U32,
+    #[keyword = "start-column"]
+    pub start_column: ::noble_idl_runtime::U32,
+    #[keyword = "end-line"]
+    pub end_line: ::noble_idl_runtime::U32,
+    #[keyword = "end-column"]
+    pub end_column: ::noble_idl_runtime::U32,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for NobleIdlSourceSpan {
+    fn default() -> Self {
+        NobleIdlSourceSpan {
+            start_line: ::std::default::Default::default(),
+            start_column: ::std::default::Default::default(),
+            end_line: ::std::default::Default::default(),
+            end_column: ::std::default::Default::default(),
+        }
+    }
+}
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+#[constructor = "package-info"]
+pub struct PackageInfo {
+    #[keyword = "name"]
+    pub name: ::std::boxed::Box<crate::PackageName>,
+    #[keyword = "annotations"]
+    pub annotations: ::noble_idl_runtime::List<::std::boxed::Box<crate::Annotation>>,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for PackageInfo {
+    fn default() -> Self {
+        PackageInfo {
+            name: ::std::default::Default::default(),
+            annotations: ::std::default::Default::default(),
+        }
+    }
 }
 #[derive(
     ::std::fmt::Debug,
@@ -628,6 +1337,12 @@ pub struct PackageName(
     #[vararg]
     pub ::noble_idl_runtime::List<::noble_idl_runtime::String>,
 );
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for PackageName {
+    fn default() -> Self {
+        PackageName(::std::default::Default::default())
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -643,6 +1358,15 @@ pub struct QualifiedName(
     pub ::std::boxed::Box<crate::PackageName>,
     pub ::noble_idl_runtime::String,
 );
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for QualifiedName {
+    fn default() -> Self {
+        QualifiedName(
+            ::std::default::Default::default(),
+            ::std::default::Default::default(),
+        )
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -659,6 +1383,19 @@ pub struct RecordDefinition {
         ::std::boxed::Box<crate::EsexprRecordOptions>,
     >,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for RecordDefinition {
+    fn default() -> Self {
+        RecordDefinition {
+            fields: ::std::default::Default::default(),
+            esexpr_options: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::EsexprRecordOptions>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::EsexprRecordOptions>>,
+            >>::from(::std::option::Option::None),
+        }
+    }
+}
 #[derive(
     ::std::fmt::Debug,
     ::std::clone::Clone,
@@ -676,6 +1413,154 @@ pub struct RecordField {
     pub esexpr_options: ::noble_idl_runtime::OptionalField<
         ::std::boxed::Box<crate::EsexprRecordFieldOptions>,
     >,
+    #[keyword = "deprecated"]
+    #[optional]
+    pub deprecated: ::noble_idl_runtime::OptionalField<
+        ::std::boxed::Box<crate::Deprecation>,
+    >,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustAnnEnum {
+    #[constructor = "derive"]
+    Derive(::noble_idl_runtime::String),
+    #[constructor = "no-derive"]
+    NoDerive(::noble_idl_runtime::String),
+    #[constructor = "serde"]
+    Serde,
+    #[constructor = "builder"]
+    Builder,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustAnnEnumCase {
+    #[constructor = "unit"]
+    Unit,
+    #[constructor = "tuple"]
+    Tuple,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustAnnExternType {
+    #[constructor = "mapped-to"]
+    MappedTo {
+        name: ::noble_idl_runtime::String,
+        #[vararg]
+        args: ::noble_idl_runtime::List<::std::boxed::Box<crate::RustMappedType>>,
+    },
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustAnnPackage {
+    #[constructor = "derive"]
+    Derive(::noble_idl_runtime::String),
+    #[constructor = "boxed"]
+    Boxed,
+    #[constructor = "serde"]
+    Serde,
+    #[constructor = "builder"]
+    Builder,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustAnnRecord {
+    #[constructor = "unit"]
+    Unit,
+    #[constructor = "tuple"]
+    Tuple,
+    #[constructor = "derive"]
+    Derive(::noble_idl_runtime::String),
+    #[constructor = "no-derive"]
+    NoDerive(::noble_idl_runtime::String),
+    #[constructor = "serde"]
+    Serde,
+    #[constructor = "builder"]
+    Builder,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustAnnRecordField {
+    #[constructor = "boxed"]
+    Boxed,
+    #[constructor = "unboxed"]
+    Unboxed,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustAnnSimpleEnum {
+    #[constructor = "derive"]
+    Derive(::noble_idl_runtime::String),
+    #[constructor = "no-derive"]
+    NoDerive(::noble_idl_runtime::String),
+    #[constructor = "serde"]
+    Serde,
+    #[constructor = "default-case"]
+    DefaultCase(::noble_idl_runtime::String),
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustAnnTypeParameter {
+    #[constructor = "boxed-usage"]
+    BoxedUsage,
+}
+#[allow(non_camel_case_types)]
+#[derive(
+    ::std::fmt::Debug,
+    ::std::clone::Clone,
+    ::std::cmp::PartialEq,
+    ::esexpr::ESExprCodec
+)]
+pub enum RustMappedType {
+    #[inline_value]
+    TypeName(::noble_idl_runtime::String),
+    #[constructor = "apply"]
+    Apply {
+        name: ::noble_idl_runtime::String,
+        #[vararg]
+        args: ::noble_idl_runtime::List<::std::boxed::Box<crate::RustMappedType>>,
+    },
+    #[constructor = "type-parameter"]
+    TypeParameter(::noble_idl_runtime::String),
 }
 #[derive(
     ::std::fmt::Debug,
@@ -693,6 +1578,32 @@ pub struct SimpleEnumCase {
     >,
     #[keyword = "annotations"]
     pub annotations: ::noble_idl_runtime::List<::std::boxed::Box<crate::Annotation>>,
+    #[keyword = "deprecated"]
+    #[optional]
+    pub deprecated: ::noble_idl_runtime::OptionalField<
+        ::std::boxed::Box<crate::Deprecation>,
+    >,
+}
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for SimpleEnumCase {
+    fn default() -> Self {
+        SimpleEnumCase {
+            name: ::std::default::Default::default(),
+            esexpr_options: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::EsexprSimpleEnumCaseOptions>,
+            > as ::std::convert::From<
+                ::std::option::Option<
+                    ::std::boxed::Box<crate::EsexprSimpleEnumCaseOptions>,
+                >,
+            >>::from(::std::option::Option::None),
+            annotations: ::std::default::Default::default(),
+            deprecated: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::Deprecation>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::Deprecation>>,
+            >>::from(::std::option::Option::None),
+        }
+    }
 }
 #[derive(
     ::std::fmt::Debug,
@@ -710,6 +1621,19 @@ pub struct SimpleEnumDefinition {
         ::std::boxed::Box<crate::EsexprSimpleEnumOptions>,
     >,
 }
+#[allow(clippy::useless_conversion, clippy::derivable_impls)]
+impl ::std::default::Default for SimpleEnumDefinition {
+    fn default() -> Self {
+        SimpleEnumDefinition {
+            cases: ::std::default::Default::default(),
+            esexpr_options: <::noble_idl_runtime::OptionalField<
+                ::std::boxed::Box<crate::EsexprSimpleEnumOptions>,
+            > as ::std::convert::From<
+                ::std::option::Option<::std::boxed::Box<crate::EsexprSimpleEnumOptions>>,
+            >>::from(::std::option::Option::None),
+        }
+    }
+}
 #[allow(non_camel_case_types)]
 #[derive(
     ::std::fmt::Debug,
@@ -0,0 +1,127 @@
+use esexpr::{ESExpr, ESExprCodec};
+
+use crate::{check_protocol_version, NobleIdlDiagnosticSeverity, NobleIDLPluginExecutor, NobleIdlGenerationRequest, NobleIdlGenerationResult, NobleIdlPluginCapability, NobleIdlPluginDiagnostic};
+
+pub const PROTOCOL_VERSION_SYMBOL: &[u8] = b"nobleidl_plugin_protocol_version\0";
+pub const GENERATE_SYMBOL: &[u8] = b"nobleidl_plugin_generate\0";
+pub const ALLOC_SYMBOL: &[u8] = b"nobleidl_plugin_alloc\0";
+pub const FREE_SYMBOL: &[u8] = b"nobleidl_plugin_free\0";
+
+pub type ProtocolVersionFn = unsafe extern "C" fn() -> u32;
+
+// Takes an ESExpr binary encoded NobleIdlGenerationRequest and returns an ESExpr binary encoded NobleIdlGenerationResult.
+// The returned buffer is owned by the plugin and must be released with the plugin's free function.
+pub type GenerateFn = unsafe extern "C" fn(request: *const u8, request_size: usize, result_size: *mut usize) -> *mut u8;
+
+pub type AllocFn = unsafe extern "C" fn(size: usize) -> *mut u8;
+pub type FreeFn = unsafe extern "C" fn(ptr: *mut u8, size: usize);
+
+
+/// # Safety
+/// The returned buffer must be released with `free` using the same size.
+pub unsafe fn alloc(size: usize) -> *mut u8 {
+    if size == 0 {
+        return std::ptr::NonNull::dangling().as_ptr();
+    }
+
+    std::alloc::alloc(std::alloc::Layout::array::<u8>(size).unwrap())
+}
+
+/// # Safety
+/// `ptr` must have been returned by `alloc` with the same size.
+pub unsafe fn free(ptr: *mut u8, size: usize) {
+    if size == 0 {
+        return;
+    }
+
+    std::alloc::dealloc(ptr, std::alloc::Layout::array::<u8>(size).unwrap());
+}
+
+/// # Safety
+/// `size` must be valid for writes. The returned buffer must be released with `free`.
+pub unsafe fn export_buffer(data: Vec<u8>, size: *mut usize) -> *mut u8 {
+    let buff = alloc(data.len());
+
+    if buff.is_null() {
+        return buff;
+    }
+
+    std::ptr::copy(data.as_ptr(), buff, data.len());
+
+    size.write_unaligned(data.len());
+
+    buff
+}
+
+// Errors are reported to the compiler as diagnostics because the result has no other way to represent a failure.
+pub fn generate_serialized<P>(plugin: &P, request: &[u8]) -> Vec<u8>
+where
+    P: NobleIDLPluginExecutor,
+    P::LanguageOptions: ESExprCodec,
+{
+    let result = generate_from_bytes(plugin, request)
+        .unwrap_or_else(|message| {
+            let mut result = NobleIdlGenerationResult::new(Vec::new());
+            result.capabilities.push(NobleIdlPluginCapability::Diagnostics);
+            result.diagnostics.push(Box::new(NobleIdlPluginDiagnostic {
+                severity: NobleIdlDiagnosticSeverity::Error,
+                message,
+            }));
+            result
+        });
+
+    let mut buff = Vec::new();
+    let mut gen = esexpr_binary::ExprGenerator::new(&mut buff);
+    gen.generate(&result.encode_esexpr()).unwrap();
+    buff
+}
+
+fn generate_from_bytes<P>(plugin: &P, request: &[u8]) -> Result<NobleIdlGenerationResult, String>
+where
+    P: NobleIDLPluginExecutor,
+    P::LanguageOptions: ESExprCodec,
+{
+    let mut requests = esexpr_binary::parse(request)
+        .collect::<Result<Vec<ESExpr>, _>>()
+        .map_err(|e| format!("Could not parse generation request: {:?}", e))?;
+
+    let (Some(request), true) = (requests.pop(), requests.is_empty()) else {
+        return Err("Expected exactly one generation request".to_owned());
+    };
+
+    check_protocol_version(&request).map_err(|e| e.to_string())?;
+
+    let request = NobleIdlGenerationRequest::<P::LanguageOptions>::decode_esexpr(request)
+        .map_err(|e| format!("Could not decode generation request: {:?}", e))?;
+
+    plugin.generate(request)
+        .map_err(|e| format!("{:?}", e))
+}
+
+
+#[macro_export]
+macro_rules! export_plugin {
+    ($plugin: expr) => {
+        #[no_mangle]
+        pub extern "C" fn nobleidl_plugin_protocol_version() -> u32 {
+            $crate::PROTOCOL_VERSION
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn nobleidl_plugin_alloc(size: usize) -> *mut u8 {
+            $crate::plugin_abi::alloc(size)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn nobleidl_plugin_free(ptr: *mut u8, size: usize) {
+            $crate::plugin_abi::free(ptr, size)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn nobleidl_plugin_generate(request: *const u8, request_size: usize, result_size: *mut usize) -> *mut u8 {
+            let request = ::std::slice::from_raw_parts(request, request_size);
+            let result = $crate::plugin_abi::generate_serialized(&$plugin, request);
+            $crate::plugin_abi::export_buffer(result, result_size)
+        }
+    };
+}
@@ -0,0 +1,6 @@
+#!/bin/bash -e
+
+cd "$(dirname "$0")"
+
+mkdir -p include
+cbindgen --config cbindgen.toml --output include/noble_idl_compiler.h
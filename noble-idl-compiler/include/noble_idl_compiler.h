@@ -0,0 +1,67 @@
+#ifndef NOBLE_IDL_COMPILER_H
+#define NOBLE_IDL_COMPILER_H
+
+/* Generated by cbindgen. Do not edit. Regenerate with generate-header.sh. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+uint8_t *nobleidl_alloc(size_t size);
+
+void nobleidl_free(uint8_t *ptr, size_t size);
+
+// Compiles the sources in an ESExpr binary encoded `NobleIdlCompileModelOptions`.
+// Returns an ESExpr binary encoded `NobleIdlCompileModelResult` containing the model or error messages.
+// Use `nobleidl_check_model` for errors with locations.
+//
+// # Safety
+// `options` must point to `options_size` readable bytes and `result_size` must be valid for writes.
+// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+uint8_t *nobleidl_compile_model(uint8_t *options,
+                                size_t options_size,
+                                size_t *result_size);
+
+// Parses and checks the sources in an ESExpr binary encoded `NobleIdlCompileModelOptions`.
+// Returns an ESExpr binary encoded `NobleIdlCheckResult` containing the model or structured errors.
+//
+// # Safety
+// `options` must point to `options_size` readable bytes and `result_size` must be valid for writes.
+// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+uint8_t *nobleidl_check_model(const uint8_t *options,
+                              size_t options_size,
+                              size_t *result_size);
+
+// Parses the sources in an ESExpr binary encoded `NobleIdlCompileModelOptions` without checking the model.
+// Returns an ESExpr binary encoded `NobleIdlCheckResult` without a model.
+//
+// # Safety
+// `options` must point to `options_size` readable bytes and `result_size` must be valid for writes.
+// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+uint8_t *nobleidl_parse(const uint8_t *options,
+                        size_t options_size,
+                        size_t *result_size);
+
+// Formats the source in an ESExpr binary encoded `NobleIdlFormatOptions`.
+// Returns an ESExpr binary encoded `NobleIdlFormatResult`.
+//
+// # Safety
+// `options` must point to `options_size` readable bytes and `result_size` must be valid for writes.
+// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+uint8_t *nobleidl_format(const uint8_t *options,
+                         size_t options_size,
+                         size_t *result_size);
+
+// Returns the compiler version as a UTF-8 string (not null terminated).
+//
+// # Safety
+// `result_size` must be valid for writes.
+// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+uint8_t *nobleidl_version(size_t *result_size);
+
+// Returns the version of the plugin protocol used by this compiler.
+uint32_t nobleidl_protocol_version(void);
+
+#endif  /* NOBLE_IDL_COMPILER_H */
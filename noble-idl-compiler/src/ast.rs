@@ -1,14 +1,35 @@
 use noble_idl_api::TypeParameterOwner;
-pub use noble_idl_api::{PackageName, QualifiedName, TypeParameter, Annotation, TypeParameterTypeConstraint};
+pub use noble_idl_api::{PackageName, QualifiedName, TypeParameter, Annotation, TypeParameterTypeConstraint, DefinitionVisibility, AnnotationTarget, Deprecation};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct DefinitionFile {
     pub package: PackageName,
+    pub package_annotations: Vec<Annotation>,
+    pub package_location: SourceLocation,
     pub imports: Vec<PackageName>,
+    // The location of each import, in the same order as imports.
+    pub import_locations: Vec<SourceLocation>,
     pub definitions: Vec<Definition>,
+    // The location of each definition, in the same order as definitions.
+    pub definition_locations: Vec<SourceLocation>,
+    pub annotation_scopes: Vec<AnnotationScopeDeclaration>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct SourceLocation {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AnnotationScopeDeclaration {
+    pub scope: String,
+    pub target: AnnotationTarget,
+    pub value_type: TypeExpr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Definition {
     Record(RecordDefinition),
     Enum(EnumDefinition),
@@ -31,12 +52,13 @@ impl Definition {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct RecordDefinition {
     pub name: String,
     pub type_parameters: Vec<TypeParameter>,
     pub fields: Vec<RecordField>,
     pub annotations: Vec<Annotation>,
+    pub visibility: DefinitionVisibility,
 }
 
 impl RecordDefinition {
@@ -50,6 +72,8 @@ impl RecordDefinition {
             }))),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			is_library,
+			visibility: self.visibility,
+			deprecated: None,
         }
     }
 }
@@ -68,17 +92,19 @@ impl RecordField {
             field_type: Box::new(self.field_type.into_api()),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			esexpr_options: None,
+			deprecated: None,
         }
     }
 }
 
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct EnumDefinition {
     pub name: String,
     pub type_parameters: Vec<TypeParameter>,
     pub cases: Vec<EnumCase>,
     pub annotations: Vec<Annotation>,
+    pub visibility: DefinitionVisibility,
 }
 
 impl EnumDefinition {
@@ -92,11 +118,13 @@ impl EnumDefinition {
             }))),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			is_library,
+			visibility: self.visibility,
+			deprecated: None,
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct EnumCase {
     pub name: String,
     pub fields: Vec<RecordField>,
@@ -110,16 +138,18 @@ impl EnumCase {
             fields: self.fields.into_iter().map(RecordField::into_api).map(Box::new).collect(),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			esexpr_options: None,
+			deprecated: None,
         }
     }
 }
 
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct SimpleEnumDefinition {
     pub name: String,
     pub cases: Vec<SimpleEnumCase>,
     pub annotations: Vec<Annotation>,
+    pub visibility: DefinitionVisibility,
 }
 
 impl SimpleEnumDefinition {
@@ -133,11 +163,13 @@ impl SimpleEnumDefinition {
             }))),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			is_library,
+			visibility: self.visibility,
+			deprecated: None,
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct SimpleEnumCase {
     pub name: String,
     pub annotations: Vec<Annotation>,
@@ -149,15 +181,17 @@ impl SimpleEnumCase {
             name: self.name,
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			esexpr_options: None,
+			deprecated: None,
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ExternTypeDefinition {
     pub name: String,
     pub type_parameters: Vec<TypeParameter>,
     pub annotations: Vec<Annotation>,
+    pub visibility: DefinitionVisibility,
 }
 
 impl ExternTypeDefinition {
@@ -170,17 +204,20 @@ impl ExternTypeDefinition {
 			}))),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			is_library,
+			visibility: self.visibility,
+			deprecated: None,
         }
     }
 }
 
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct InterfaceDefinition {
     pub name: String,
     pub type_parameters: Vec<TypeParameter>,
     pub methods: Vec<InterfaceMethod>,
     pub annotations: Vec<Annotation>,
+    pub visibility: DefinitionVisibility,
 }
 
 impl InterfaceDefinition {
@@ -193,11 +230,13 @@ impl InterfaceDefinition {
             }))),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			is_library,
+			visibility: self.visibility,
+			deprecated: None,
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct InterfaceMethod {
     pub name: String,
     pub type_parameters: Vec<TypeParameter>,
@@ -216,11 +255,12 @@ impl InterfaceMethod {
             return_type: Box::new(self.return_type.into_api()),
 			throws: self.throws.map(TypeExpr::into_api).map(Box::new),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
+			deprecated: None,
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct InterfaceMethodParameter {
     pub name: String,
     pub parameter_type: TypeExpr,
@@ -238,11 +278,12 @@ impl InterfaceMethodParameter {
 }
 
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ExceptionTypeDefinition {
     pub name: String,
 	pub information: TypeExpr,
     pub annotations: Vec<Annotation>,
+    pub visibility: DefinitionVisibility,
 }
 
 impl ExceptionTypeDefinition {
@@ -255,6 +296,8 @@ impl ExceptionTypeDefinition {
             }))),
             annotations: self.annotations.into_iter().map(Box::new).collect(),
 			is_library,
+			visibility: self.visibility,
+			deprecated: None,
         }
     }
 }
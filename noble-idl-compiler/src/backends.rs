@@ -0,0 +1,60 @@
+use std::{ffi::OsString, path::{Path, PathBuf}};
+
+use crate::ProcessPlugin;
+
+pub struct BuiltinBackend {
+    pub name: &'static str,
+    pub executable: &'static str,
+}
+
+pub const BUILTIN_BACKENDS: &[BuiltinBackend] = &[
+    BuiltinBackend {
+        name: "rust",
+        executable: "noble-idl-plugin-rust",
+    },
+];
+
+#[derive(Debug)]
+pub enum BackendError {
+    UnknownBackend(String),
+    ExecutableNotFound(String),
+}
+
+impl BuiltinBackend {
+    pub fn find(name: &str) -> Option<&'static BuiltinBackend> {
+        BUILTIN_BACKENDS.iter().find(|backend| backend.name == name)
+    }
+
+    // Looks for the executable next to the current executable, then on PATH.
+    pub fn locate(&self) -> Option<PathBuf> {
+        let file_name = format!("{}{}", self.executable, std::env::consts::EXE_SUFFIX);
+
+        let exe_dir = std::env::current_exe().ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf));
+
+        let path_dirs = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        exe_dir.into_iter()
+            .chain(path_dirs)
+            .map(|dir| dir.join(&file_name))
+            .find(|path| path.is_file())
+    }
+
+    pub fn plugin(&self, plugin_arguments: Vec<OsString>) -> Result<ProcessPlugin, BackendError> {
+        let plugin_command = self.locate()
+            .ok_or_else(|| BackendError::ExecutableNotFound(self.executable.to_owned()))?;
+
+        Ok(ProcessPlugin {
+            plugin_command: plugin_command.into_os_string(),
+            plugin_arguments,
+        })
+    }
+}
+
+pub fn backend_plugin(name: &str, plugin_arguments: Vec<OsString>) -> Result<ProcessPlugin, BackendError> {
+    BuiltinBackend::find(name)
+        .ok_or_else(|| BackendError::UnknownBackend(name.to_owned()))?
+        .plugin(plugin_arguments)
+}
@@ -0,0 +1,69 @@
+use esexpr::ESExpr;
+use noble_idl_api::{NobleIDLPluginExecutor, NobleIdlGenerationRequest, NobleIdlGenerationResult};
+use noble_idl_compiler::{PluginInvocation, ProcessPlugin, ProcessPluginError};
+#[cfg(feature = "wasm-plugin")]
+use noble_idl_compiler::{WasmPlugin, WasmPluginError};
+
+// Plugins are run as processes unless the command is a WebAssembly module.
+pub enum CliPlugin {
+    Process(ProcessPlugin),
+    #[cfg(feature = "wasm-plugin")]
+    Wasm(WasmPlugin),
+}
+
+impl CliPlugin {
+    pub fn load(plugin: ProcessPlugin) -> Result<Self, String> {
+        #[cfg(feature = "wasm-plugin")]
+        if std::path::Path::new(&plugin.plugin_command).extension().is_some_and(|ext| ext == "wasm") {
+            let mut wasm_plugin = WasmPlugin::load(&plugin.plugin_command)
+                .map_err(|e| format!("Could not load WebAssembly plugin: {:?}", e))?;
+
+            wasm_plugin.plugin_arguments = plugin.plugin_arguments.iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+
+            return Ok(CliPlugin::Wasm(wasm_plugin));
+        }
+
+        Ok(CliPlugin::Process(plugin))
+    }
+
+    pub fn load_invocation(invocation: PluginInvocation<ProcessPlugin>) -> Result<PluginInvocation<Self>, String> {
+        Ok(PluginInvocation {
+            name: invocation.name,
+            plugin: CliPlugin::load(invocation.plugin)?,
+            plugin_options: invocation.plugin_options,
+            output_dir: invocation.output_dir,
+        })
+    }
+}
+
+impl NobleIDLPluginExecutor for CliPlugin {
+    type LanguageOptions = ESExpr;
+    type Error = CliPluginError;
+
+    fn generate(&self, request: NobleIdlGenerationRequest<Self::LanguageOptions>) -> Result<NobleIdlGenerationResult, Self::Error> {
+        match self {
+            CliPlugin::Process(plugin) => plugin.generate(request).map_err(CliPluginError::ProcessPluginError),
+            #[cfg(feature = "wasm-plugin")]
+            CliPlugin::Wasm(plugin) => plugin.generate(request).map_err(CliPluginError::WasmPluginError),
+        }
+    }
+}
+
+pub enum CliPluginError {
+    ProcessPluginError(ProcessPluginError),
+    #[cfg(feature = "wasm-plugin")]
+    WasmPluginError(WasmPluginError),
+}
+
+// Errors are shown as the error of the underlying plugin.
+impl std::fmt::Debug for CliPluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliPluginError::ProcessPluginError(e) => e.fmt(f),
+            #[cfg(feature = "wasm-plugin")]
+            CliPluginError::WasmPluginError(e) => e.fmt(f),
+        }
+    }
+}
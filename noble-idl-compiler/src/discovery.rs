@@ -0,0 +1,134 @@
+use std::{ffi::OsStr, path::{Path, PathBuf}};
+
+use derive_more::From;
+
+pub const IDL_EXTENSION: &str = "nidl";
+
+
+#[derive(From, Debug)]
+pub enum DiscoveryError {
+    IOError(std::io::Error),
+    PatternError(glob::PatternError),
+    GlobError(glob::GlobError),
+    #[from(ignore)]
+    NotFound(PathBuf),
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct Excludes {
+    patterns: Vec<glob::Pattern>,
+}
+
+impl Excludes {
+    // Patterns are made absolute so that they match paths found relative to any base directory.
+    pub fn new<S: AsRef<str>>(base_dir: &Path, patterns: &[S]) -> Result<Self, DiscoveryError> {
+        let patterns = patterns.iter()
+            .map(|pattern| {
+                let pattern = std::path::absolute(base_dir.join(pattern.as_ref()))?;
+                Ok(glob::Pattern::new(&pattern.to_string_lossy())?)
+            })
+            .collect::<Result<Vec<_>, DiscoveryError>>()?;
+
+        Ok(Excludes { patterns })
+    }
+
+    pub fn extend(&mut self, other: &Excludes) {
+        self.patterns.extend(other.patterns.iter().cloned());
+    }
+
+    // A path is excluded if it or any of its parent directories match.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        let Ok(path) = std::path::absolute(path) else { return false; };
+        path.ancestors().any(|p| self.patterns.iter().any(|pattern| pattern.matches_path(p)))
+    }
+}
+
+
+// Inputs may be files, directories (searched recursively for .nidl files) or glob patterns.
+// Results are sorted and deduplicated so that the model is built in the same order on every run.
+pub fn discover_files<P: AsRef<Path>>(base_dir: &Path, inputs: &[P], excludes: &Excludes) -> Result<Vec<PathBuf>, DiscoveryError> {
+    let mut files = Vec::new();
+
+    for input in inputs {
+        let input = base_dir.join(input);
+
+        if is_glob_pattern(&input) {
+            for path in glob::glob(&input.to_string_lossy())? {
+                add_path(&mut files, &path?, excludes)?;
+            }
+        }
+        else if input.exists() {
+            add_path(&mut files, &input, excludes)?;
+        }
+        else {
+            return Err(DiscoveryError::NotFound(input));
+        }
+    }
+
+    files.sort();
+    files.dedup();
+
+    Ok(files)
+}
+
+// The directories that new files matching the inputs may be added to.
+// These are the directory inputs and the directories that glob patterns start from.
+pub fn input_dirs<P: AsRef<Path>>(base_dir: &Path, inputs: &[P]) -> Vec<PathBuf> {
+    inputs.iter()
+        .map(|input| base_dir.join(input))
+        .filter_map(|input| {
+            if is_glob_pattern(&input) {
+                let dir = input.components()
+                    .take_while(|c| !is_glob_pattern(Path::new(c.as_os_str())))
+                    .collect::<PathBuf>();
+
+                Some(if dir.as_os_str().is_empty() { PathBuf::from(".") } else { dir })
+            }
+            else if input.is_dir() {
+                Some(input)
+            }
+            else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn is_glob_pattern(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+fn add_path(files: &mut Vec<PathBuf>, path: &Path, excludes: &Excludes) -> Result<(), DiscoveryError> {
+    if excludes.is_excluded(path) {
+        return Ok(());
+    }
+
+    if path.is_dir() {
+        scan_dir(files, path, excludes)?;
+    }
+    else {
+        files.push(path.to_owned());
+    }
+
+    Ok(())
+}
+
+fn scan_dir(files: &mut Vec<PathBuf>, dir: &Path, excludes: &Excludes) -> Result<(), DiscoveryError> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if excludes.is_excluded(&path) {
+            continue;
+        }
+
+        if path.is_dir() {
+            scan_dir(files, &path, excludes)?;
+        }
+        else if path.extension() == Some(OsStr::new(IDL_EXTENSION)) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
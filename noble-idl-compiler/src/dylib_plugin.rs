@@ -0,0 +1,97 @@
+use std::ffi::OsStr;
+
+use derive_more::From;
+use esexpr::{ESExpr, ESExprCodec};
+use noble_idl_api::{check_protocol_version, plugin_abi, NobleIDLPluginExecutor, NobleIdlGenerationRequest, NobleIdlGenerationResult, ProtocolError, PROTOCOL_VERSION};
+
+pub struct DylibPlugin {
+    generate: plugin_abi::GenerateFn,
+    free: plugin_abi::FreeFn,
+
+    // Must outlive the function pointers above.
+    _library: libloading::Library,
+}
+
+impl DylibPlugin {
+    /// # Safety
+    /// Loading a library runs its initialization code, and the library must implement the plugin ABI
+    /// from `noble_idl_api::plugin_abi` with the declared signatures.
+    pub unsafe fn load<P: AsRef<OsStr>>(path: P) -> Result<Self, DylibPluginError> {
+        let library = libloading::Library::new(path)?;
+
+        let protocol_version = library.get::<plugin_abi::ProtocolVersionFn>(plugin_abi::PROTOCOL_VERSION_SYMBOL)?;
+        let protocol_version = protocol_version();
+        if protocol_version != PROTOCOL_VERSION {
+            return Err(DylibPluginError::ProtocolError(ProtocolError::IncompatibleVersion {
+                expected: PROTOCOL_VERSION,
+                actual: protocol_version,
+            }));
+        }
+
+        let generate = *library.get::<plugin_abi::GenerateFn>(plugin_abi::GENERATE_SYMBOL)?;
+        let free = *library.get::<plugin_abi::FreeFn>(plugin_abi::FREE_SYMBOL)?;
+
+        Ok(DylibPlugin {
+            generate,
+            free,
+            _library: library,
+        })
+    }
+}
+
+impl NobleIDLPluginExecutor for DylibPlugin {
+    type LanguageOptions = ESExpr;
+    type Error = DylibPluginError;
+
+    fn generate(&self, request: NobleIdlGenerationRequest<Self::LanguageOptions>) -> Result<NobleIdlGenerationResult, Self::Error> {
+        let offered_capabilities = request.capabilities.clone();
+
+        let mut request_bytes = Vec::new();
+        let mut gen = esexpr_binary::ExprGenerator::new(&mut request_bytes);
+        gen.generate(&request.encode_esexpr())?;
+
+        let result_bytes = unsafe {
+            let mut result_size = 0;
+            let result = (self.generate)(request_bytes.as_ptr(), request_bytes.len(), &mut result_size);
+            if result.is_null() {
+                return Err(DylibPluginError::NullResult);
+            }
+
+            let result_bytes = std::slice::from_raw_parts(result, result_size).to_vec();
+            (self.free)(result, result_size);
+            result_bytes
+        };
+
+        let mut results = esexpr_binary::parse(&result_bytes[..])
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let num_res = results.len();
+        let res = match results.pop() {
+            Some(res) if num_res == 1 => res,
+            _ => return Err(DylibPluginError::UnexpectedGenerationResult {
+                expected_count: 1,
+                actual_count: num_res,
+            }),
+        };
+
+        check_protocol_version(&res)?;
+        let res = NobleIdlGenerationResult::decode_esexpr(res)?;
+        res.check_capabilities(&offered_capabilities)?;
+        Ok(res)
+    }
+}
+
+#[derive(From, Debug)]
+pub enum DylibPluginError {
+    LibraryError(libloading::Error),
+    ESExprParseError(esexpr_binary::ParseError),
+    ESExprGeneratorError(esexpr_binary::GeneratorError),
+    ESExprDecodeError(esexpr::DecodeError),
+    ProtocolError(ProtocolError),
+    NullResult,
+    UnexpectedGenerationResult {
+        expected_count: usize,
+        actual_count: usize,
+    },
+}
+
@@ -0,0 +1,88 @@
+use crate::{parse_definition_file, Error};
+
+const INDENT: &str = "    ";
+
+// Normalizes indentation, trailing whitespace, and blank lines.
+// The source is only validated by the parser; formatting is done line by line
+// so that comments and the text of annotation values are preserved.
+pub fn format_source<PE>(source: &str) -> Result<String, Error<PE>> {
+    parse_definition_file::<PE>(source, None)?;
+
+    let mut formatted = String::new();
+    let mut depth: usize = 0;
+    let mut pending_blank_line = false;
+    let mut prev_opened = false;
+    let mut state = LineState::default();
+
+    for line in source.lines() {
+        if state.in_string {
+            // Lines inside a multi-line string are kept verbatim.
+            formatted.push_str(line);
+            formatted.push('\n');
+
+            let (opened, closed) = state.scan(line);
+            depth = (depth + opened).saturating_sub(closed);
+            continue;
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            pending_blank_line = !formatted.is_empty();
+            continue;
+        }
+
+        let leading_closers = line.chars().take_while(|c| matches!(c, '}' | ')' | ']')).count();
+
+        if pending_blank_line && !prev_opened && leading_closers == 0 {
+            formatted.push('\n');
+        }
+        pending_blank_line = false;
+
+        for _ in 0..depth.saturating_sub(leading_closers) {
+            formatted.push_str(INDENT);
+        }
+        formatted.push_str(line);
+        formatted.push('\n');
+
+        let (opened, closed) = state.scan(line);
+        depth = (depth + opened).saturating_sub(closed);
+        prev_opened = opened > closed;
+    }
+
+    Ok(formatted)
+}
+
+#[derive(Default)]
+struct LineState {
+    in_string: bool,
+}
+
+impl LineState {
+    // Counts the brackets opened and closed on a line, ignoring strings and comments.
+    fn scan(&mut self, line: &str) -> (usize, usize) {
+        let mut opened = 0;
+        let mut closed = 0;
+
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if self.in_string {
+                match c {
+                    '\\' => { chars.next(); },
+                    '"' => self.in_string = false,
+                    _ => {},
+                }
+                continue;
+            }
+
+            match c {
+                '"' => self.in_string = true,
+                '/' if chars.peek() == Some(&'/') => break,
+                '{' | '(' | '[' => opened += 1,
+                '}' | ')' | ']' => closed += 1,
+                _ => {},
+            }
+        }
+
+        (opened, closed)
+    }
+}
@@ -1,36 +1,173 @@
-use std::{ffi::OsString, path::{Path, PathBuf}};
+use std::{collections::HashMap, ffi::OsString, path::{Component, Path, PathBuf}};
 
 
 use derive_more::From;
 use esexpr::ESExpr;
 
 pub mod ast;
+pub mod backends;
+pub mod discovery;
+pub mod format;
+pub mod lint;
+#[cfg(not(target_family = "wasm"))]
+mod dylib_plugin;
 pub mod model;
 pub mod parser;
-
-use model::{CheckError, ModelBuilder};
-use noble_idl_api::{NobleIdlCompileModelOptions, NobleIdlCompileModelResult, NobleIdlGenerationRequest, NobleIdlGenerationResult, NobleIdlModel, NobleIDLPluginExecutor};
+pub mod project;
+#[cfg(feature = "wasm-plugin")]
+mod wasm_plugin;
+
+#[cfg(not(target_family = "wasm"))]
+pub use dylib_plugin::{DylibPlugin, DylibPluginError};
+#[cfg(feature = "wasm-plugin")]
+pub use wasm_plugin::{WasmPlugin, WasmPluginError};
+use lint::{LintConfiguration, LintDiagnostic};
+use model::{CheckError, CheckWarning, CheckedModel, FileScope, ModelBuilder};
+use noble_idl_api::{check_protocol_version, NobleIdlCheckResult, NobleIdlCompileError, NobleIdlCompileErrorKind, NobleIdlCompileModelOptions, NobleIdlCompileModelResult, NobleIdlDiagnosticSeverity, NobleIdlFormatOptions, NobleIdlFormatResult, NobleIdlGenerationRequest, NobleIdlGenerationResult, NobleIdlModel, NobleIDLPluginExecutor, NobleIdlPluginDiagnostic, NobleIdlSourceSpan, ProtocolError};
 use esexpr::ESExprCodec;
 
 
 #[derive(From, Debug)]
 pub enum Error<PE> {
     #[from(ignore)]
-    ParseError(String),
-    ModelCheckError(CheckError),
+    ParseError(SourceParseError),
+    #[from(ignore)]
+    ModelCheckError(CheckError, Option<ast::SourceLocation>),
     IOError(std::io::Error),
     FormatError(std::fmt::Error),
     UnmappedPackageError(ast::PackageName),
     #[from(ignore)]
+    LintError(Vec<LintDiagnostic>),
+    #[from(ignore)]
+    InvalidOutputDir,
+    #[from(ignore)]
+    InvalidGeneratedFilePath(String),
+    #[from(ignore)]
     PluginError(PE),
 }
 
-impl <'input, PE> From<nom::Err<nom::error::Error<&str>>> for Error<PE> {
-    fn from(value: nom::Err<nom::error::Error<&str>>) -> Self {
-        Error::ParseError(format!("{:?}", value))
+impl <PE: std::fmt::Debug> Error<PE> {
+    pub fn into_compile_errors(self) -> Vec<NobleIdlCompileError> {
+        let (kind, message) = match self {
+            Error::ParseError(e) => return vec![ e.into_compile_error() ],
+            Error::LintError(diagnostics) => return diagnostics.into_iter().map(lint_compile_error).collect(),
+            Error::ModelCheckError(e, location) => return vec![
+                NobleIdlCompileError {
+                    kind: NobleIdlCompileErrorKind::CheckError,
+                    message: e.to_string(),
+                    file: location.as_ref().and_then(|location| location.file.clone()),
+                    span: location.as_ref().map(|location| Box::new(source_span(location))),
+                },
+            ],
+            Error::UnmappedPackageError(e) => (NobleIdlCompileErrorKind::CheckError, format!("Unmapped package: {:?}", e)),
+            Error::IOError(e) => (NobleIdlCompileErrorKind::IoError, e.to_string()),
+            e => (NobleIdlCompileErrorKind::OptionsError, format!("{:?}", e)),
+        };
+
+        vec![
+            NobleIdlCompileError {
+                kind,
+                message,
+                file: None,
+                span: None,
+            },
+        ]
+    }
+}
+
+impl <PE> From<CheckError> for Error<PE> {
+    fn from(value: CheckError) -> Self {
+        Error::ModelCheckError(value, None)
+    }
+}
+
+fn lint_compile_error(diagnostic: LintDiagnostic) -> NobleIdlCompileError {
+    NobleIdlCompileError {
+        kind: NobleIdlCompileErrorKind::CheckError,
+        message: format!("{} [{}]", diagnostic.message, diagnostic.rule),
+        file: diagnostic.location.as_ref().and_then(|location| location.file.clone()),
+        span: diagnostic.location.as_ref().map(|location| Box::new(source_span(location))),
+    }
+}
+
+fn source_span(location: &ast::SourceLocation) -> NobleIdlSourceSpan {
+    let line = location.line.try_into().unwrap_or(u32::MAX);
+    let column = location.column.try_into().unwrap_or(u32::MAX);
+
+    NobleIdlSourceSpan {
+        start_line: line,
+        start_column: column,
+        end_line: line,
+        end_column: column,
     }
 }
 
+#[derive(Debug)]
+pub struct SourceParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl SourceParseError {
+    fn new(source: &str, file: Option<&Path>, e: nom::Err<nom::error::Error<&str>>) -> Self {
+        let (remaining, message) = match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => (e.input, format!("Unexpected input ({:?})", e.code)),
+            nom::Err::Incomplete(_) => ("", "Unexpected end of input".to_owned()),
+        };
+
+        let (line, column) = line_column(&source[..source.len() - remaining.len()]);
+
+        SourceParseError {
+            file: file.map(|file| file.to_string_lossy().into_owned()),
+            line,
+            column,
+            message,
+        }
+    }
+
+    pub fn into_compile_error(self) -> NobleIdlCompileError {
+        let line = self.line.try_into().unwrap_or(u32::MAX);
+        let column = self.column.try_into().unwrap_or(u32::MAX);
+
+        NobleIdlCompileError {
+            kind: NobleIdlCompileErrorKind::ParseError,
+            message: self.message,
+            file: self.file,
+            span: Some(Box::new(NobleIdlSourceSpan {
+                start_line: line,
+                start_column: column,
+                end_line: line,
+                end_column: column,
+            })),
+        }
+    }
+}
+
+// The line and column (both starting at 1) at the end of the consumed source.
+fn line_column(consumed: &str) -> (usize, usize) {
+    let line = consumed.matches('\n').count() + 1;
+    let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+    let column = consumed[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+pub fn parse_definition_file<PE>(source: &str, file: Option<&Path>) -> Result<ast::DefinitionFile, Error<PE>> {
+    let (_, mut def_file) = parser::definition_file(source)
+        .map_err(|e| Error::ParseError(SourceParseError::new(source, file, e)))?;
+
+    let locations = std::iter::once(&mut def_file.package_location)
+        .chain(&mut def_file.import_locations)
+        .chain(&mut def_file.definition_locations);
+
+    for location in locations {
+        location.file = file.map(|file| file.to_string_lossy().into_owned());
+    }
+
+    Ok(def_file)
+}
+
 impl From<CompileModelError> for Error<CompileModelError> {
     fn from(value: CompileModelError) -> Self {
         Error::PluginError(value)
@@ -57,6 +194,7 @@ impl NobleIDLPluginExecutor for ProcessPlugin {
 
         let mut stdin: std::process::ChildStdin = child.stdin.take().unwrap();
 
+        let offered_capabilities = request.capabilities.clone();
         let model = request.encode_esexpr();
 
 		let mut gen = esexpr_binary::ExprGenerator::new(&mut stdin);
@@ -71,7 +209,9 @@ impl NobleIDLPluginExecutor for ProcessPlugin {
         let mut results = esexpr_binary::parse(stdout)
             .map(|res| -> Result<_, ProcessPluginError> {
                 let res = res?;
+                check_protocol_version(&res)?;
                 let res = NobleIdlGenerationResult::decode_esexpr(res)?;
+                res.check_capabilities(&offered_capabilities)?;
                 Ok(res)
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -94,6 +234,7 @@ pub enum ProcessPluginError {
     ESExprGeneratorError(esexpr_binary::GeneratorError),
     ESExprDecodeError(esexpr::DecodeError),
     IOError(std::io::Error),
+    ProtocolError(ProtocolError),
     UnexpectedGenerationResult {
         expected_count: usize,
         actual_count: usize,
@@ -106,45 +247,236 @@ pub struct NobleIDLOptions<L> {
     pub library_files: Vec<PathBuf>,
     pub files: Vec<PathBuf>,
     pub plugin_options: L,
+    pub lints: LintConfiguration,
 }
 
 
 pub fn compile<P: NobleIDLPluginExecutor>(p: &P, options: &NobleIDLOptions<P::LanguageOptions>) -> Result<NobleIdlGenerationResult, Error<P::Error>> {
+    let checked = check_files(&options.library_files, &options.files, &options.lints)?;
+    let mut result = generate(p, checked.model, options.plugin_options.clone(), None)?;
+    add_warnings(&mut result, &checked.warnings);
+    Ok(result)
+}
+
+pub struct PluginInvocation<P: NobleIDLPluginExecutor> {
+    pub name: String,
+    pub plugin: P,
+    pub plugin_options: P::LanguageOptions,
+    pub output_dir: Option<PathBuf>,
+}
+
+pub type PluginResult<P> = Result<NobleIdlGenerationResult, Error<<P as NobleIDLPluginExecutor>::Error>>;
+
+pub fn compile_all<P>(library_files: &[PathBuf], files: &[PathBuf], lints: &LintConfiguration, invocations: &[PluginInvocation<P>]) -> Result<Vec<PluginResult<P>>, Error<P::Error>>
+where
+    P: NobleIDLPluginExecutor + Sync,
+    P::LanguageOptions: Send + Sync,
+    P::Error: Send,
+{
+    let checked = check_files(library_files, files, lints)?;
+    let results = generate_all(&checked.model, invocations)
+        .into_iter()
+        .map(|result| result.map(|mut result| {
+            add_warnings(&mut result, &checked.warnings);
+            result
+        }))
+        .collect();
+
+    Ok(results)
+}
+
+// Reports model warnings along with the diagnostics from the plugin.
+pub fn add_warnings(result: &mut NobleIdlGenerationResult, warnings: &[CheckWarning]) {
+    result.diagnostics.extend(warnings.iter().map(|warning| Box::new(NobleIdlPluginDiagnostic {
+        severity: NobleIdlDiagnosticSeverity::Warning,
+        message: warning.to_string(),
+    })));
+}
+
+pub fn generate_all<P>(model: &NobleIdlModel, invocations: &[PluginInvocation<P>]) -> Vec<PluginResult<P>>
+where
+    P: NobleIDLPluginExecutor + Sync,
+    P::LanguageOptions: Send + Sync,
+    P::Error: Send,
+{
+    std::thread::scope(|s| {
+        let handles = invocations.iter()
+            .map(|invocation| {
+                let model = model.clone();
+                s.spawn(move || generate(
+                    &invocation.plugin,
+                    model,
+                    invocation.plugin_options.clone(),
+                    invocation.output_dir.as_deref(),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        handles.into_iter()
+            .map(|handle| handle.join().expect("Plugin thread panicked"))
+            .collect::<Vec<_>>()
+    })
+}
+
+pub fn check_files<PE>(library_files: &[PathBuf], files: &[PathBuf], lints: &LintConfiguration) -> Result<CheckedModel, Error<PE>> {
     let mut model = ModelBuilder::new();
-    for file in &options.library_files {
+    for file in library_files {
         load_file(&mut model, file, true)?;
     }
 
-    for file in &options.files {
+    for file in files {
         load_file(&mut model, file, false)?;
     }
 
-    let model = model.check()?;
-    let request = NobleIdlGenerationRequest {
-        language_options: options.plugin_options.clone(),
-        model: Box::new(model),
-    };
+    check_and_lint(model, lints)
+}
+
+// Like check_files, but the library files are provided as source text, e.g. when they are embedded in a tool.
+pub fn check_files_with_library_sources<PE>(library_sources: &[&str], files: &[PathBuf], lints: &LintConfiguration) -> Result<CheckedModel, Error<PE>> {
+    let mut model = ModelBuilder::new();
+    for source in library_sources {
+        load_source(&mut model, source, true)?;
+    }
+
+    for file in files {
+        load_file(&mut model, file, false)?;
+    }
 
-    p.generate(request).map_err(Error::PluginError)
+    check_and_lint(model, lints)
 }
 
-fn load_file<PE>(model: &mut model::ModelBuilder, file: &Path, is_library: bool) -> Result<(), Error<PE>> {
+fn check_and_lint<PE>(model: ModelBuilder, lints: &LintConfiguration) -> Result<CheckedModel, Error<PE>> {
+    let locations = model.locations().clone();
+    let mut checked = model.check()
+        .map_err(|e| {
+            let location = e.definition_name().and_then(|name| locations.get(name)).cloned();
+            Error::ModelCheckError(e, location)
+        })?;
+    lint::run(&mut checked, lints).map_err(Error::LintError)?;
+    Ok(checked)
+}
 
-    let source = std::fs::read_to_string(file)?;
-    let (_, def_file) = parser::definition_file(&source)?;
+#[derive(Debug, Default)]
+pub struct ParseCache {
+    files: HashMap<PathBuf, (String, ast::DefinitionFile)>,
+}
+
+impl ParseCache {
+    fn parse<PE>(&mut self, file: &Path) -> Result<&ast::DefinitionFile, Error<PE>> {
+        let source = std::fs::read_to_string(file)?;
+
+        let is_current = self.files.get(file).is_some_and(|(cached_source, _)| *cached_source == source);
+        if !is_current {
+            let def_file = parse_definition_file(&source, Some(file))?;
+            self.files.insert(file.to_owned(), (source, def_file));
+        }
+
+        Ok(&self.files[file].1)
+    }
+}
+
+// Like check_files, but only reparses files whose contents changed since the previous call.
+pub fn check_files_cached<PE>(cache: &mut ParseCache, library_files: &[PathBuf], files: &[PathBuf], lints: &LintConfiguration) -> Result<CheckedModel, Error<PE>> {
+    cache.files.retain(|path, _| library_files.contains(path) || files.contains(path));
+
+    let mut model = ModelBuilder::new();
+    for file in library_files {
+        add_file_definitions(&mut model, cache.parse(file)?, true)?;
+    }
+
+    for file in files {
+        add_file_definitions(&mut model, cache.parse(file)?, false)?;
+    }
+
+    check_and_lint(model, lints)
+}
 
-    for def in def_file.definitions {
+fn add_file_definitions<PE>(model: &mut model::ModelBuilder, def_file: &ast::DefinitionFile, is_library: bool) -> Result<(), Error<PE>> {
+    model.add_package_annotations(&def_file.package, &def_file.package_annotations);
+
+    model.add_file_scope(FileScope {
+        package: def_file.package.clone(),
+        package_location: def_file.package_location.clone(),
+        imports: def_file.imports.clone(),
+        import_locations: def_file.import_locations.clone(),
+        definitions: def_file.definitions.iter()
+            .map(|def| ast::QualifiedName(Box::new(def_file.package.clone()), def.name().to_owned()))
+            .collect(),
+        annotation_scopes: def_file.annotation_scopes.iter()
+            .map(|decl| (decl.scope.clone(), decl.target))
+            .collect(),
+        is_library,
+    });
+
+    for (def, location) in def_file.definitions.iter().zip(&def_file.definition_locations) {
         model.add_definition(model::DefinitionInfo {
             package: def_file.package.clone(),
             imports: def_file.imports.clone(),
-            def,
+            def: def.clone(),
             is_library,
-        })?;
+            location: location.clone(),
+        }).map_err(|e| Error::ModelCheckError(e, Some(location.clone())))?;
+    }
+
+    for decl in &def_file.annotation_scopes {
+        model.add_annotation_scope(model::AnnotationScopeEntry {
+            package: def_file.package.clone(),
+            imports: def_file.imports.clone(),
+            declaration: decl.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn generate<P: NobleIDLPluginExecutor>(p: &P, model: NobleIdlModel, plugin_options: P::LanguageOptions, output_dir: Option<&Path>) -> Result<NobleIdlGenerationResult, Error<P::Error>> {
+    let mut request = NobleIdlGenerationRequest::new(plugin_options, model);
+    if let Some(output_dir) = output_dir {
+        request.output_dir = Some(output_dir.to_str().ok_or(Error::InvalidOutputDir)?.to_owned());
+    }
+
+    let mut result = p.generate(request).map_err(Error::PluginError)?;
+    write_file_contents(&mut result, output_dir)?;
+    Ok(result)
+}
+
+// Plugins may run sandboxed, so the files they return must stay inside the output directory.
+// All paths are checked before anything is written.
+fn write_file_contents<PE>(result: &mut NobleIdlGenerationResult, output_dir: Option<&Path>) -> Result<(), Error<PE>> {
+    if let Some(file) = result.file_contents.iter().find(|file| !is_relative_output_path(&file.path)) {
+        return Err(Error::InvalidGeneratedFilePath(file.path.clone()));
+    }
+
+    for file in result.file_contents.drain(..) {
+        let path = match output_dir {
+            Some(output_dir) => output_dir.join(&file.path),
+            None => PathBuf::from(&file.path),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&path, &file.content)?;
+        result.generated_files.push(path.to_string_lossy().into_owned());
     }
 
     Ok(())
 }
 
+fn is_relative_output_path(path: &str) -> bool {
+    let path = Path::new(path);
+    path.components().next().is_some() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn load_file<PE>(model: &mut model::ModelBuilder, file: &Path, is_library: bool) -> Result<(), Error<PE>> {
+
+    let source = std::fs::read_to_string(file)?;
+    let def_file = parse_definition_file(&source, Some(file))?;
+
+    add_file_definitions(model, &def_file, is_library)
+}
+
 #[derive(From, Debug)]
 pub enum CompileModelError {
     ParseError(esexpr_binary::ParseError),
@@ -164,21 +496,102 @@ pub unsafe extern "C" fn nobleidl_free(ptr: *mut u8, size: usize) {
     std::alloc::dealloc(ptr, std::alloc::Layout::array::<u8>(size).unwrap());
 }
 
+/// Compiles the sources in an ESExpr binary encoded `NobleIdlCompileModelOptions`.
+/// Returns an ESExpr binary encoded `NobleIdlCompileModelResult` containing the model or error messages.
+/// Use `nobleidl_check_model` for errors with locations.
+///
+/// # Safety
+/// `options` must point to `options_size` readable bytes and `result_size` must be valid for writes.
+/// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
 #[no_mangle]
 pub unsafe extern "C" fn nobleidl_compile_model(options: *mut u8, options_size: usize, result_size: *mut usize) -> *mut u8 {
     let options = std::slice::from_raw_parts(options, options_size);
+    export_buffer(compile_model_serialized(options), result_size)
+}
+
+/// Parses and checks the sources in an ESExpr binary encoded `NobleIdlCompileModelOptions`.
+/// Returns an ESExpr binary encoded `NobleIdlCheckResult` containing the model or structured errors.
+///
+/// # Safety
+/// `options` must point to `options_size` readable bytes and `result_size` must be valid for writes.
+/// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+#[no_mangle]
+pub unsafe extern "C" fn nobleidl_check_model(options: *const u8, options_size: usize, result_size: *mut usize) -> *mut u8 {
+    let options = std::slice::from_raw_parts(options, options_size);
+
+    let result = match decode_options::<NobleIdlCompileModelOptions>(options) {
+        Ok(options) => check_model(options),
+        Err(e) => NobleIdlCheckResult::Failure { errors: boxed_errors(e) },
+    };
+
+    export_buffer(serialize_result(result), result_size)
+}
+
+/// Parses the sources in an ESExpr binary encoded `NobleIdlCompileModelOptions` without checking the model.
+/// Returns an ESExpr binary encoded `NobleIdlCheckResult` without a model.
+///
+/// # Safety
+/// `options` must point to `options_size` readable bytes and `result_size` must be valid for writes.
+/// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+#[no_mangle]
+pub unsafe extern "C" fn nobleidl_parse(options: *const u8, options_size: usize, result_size: *mut usize) -> *mut u8 {
+    let options = std::slice::from_raw_parts(options, options_size);
 
-    let model = compile_model_serialized(options);
+    let result = match decode_options::<NobleIdlCompileModelOptions>(options) {
+        Ok(options) => parse_sources(&options),
+        Err(e) => NobleIdlCheckResult::Failure { errors: boxed_errors(e) },
+    };
+
+    export_buffer(serialize_result(result), result_size)
+}
+
+/// Formats the source in an ESExpr binary encoded `NobleIdlFormatOptions`.
+/// Returns an ESExpr binary encoded `NobleIdlFormatResult`.
+///
+/// # Safety
+/// `options` must point to `options_size` readable bytes and `result_size` must be valid for writes.
+/// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+#[no_mangle]
+pub unsafe extern "C" fn nobleidl_format(options: *const u8, options_size: usize, result_size: *mut usize) -> *mut u8 {
+    let options = std::slice::from_raw_parts(options, options_size);
+
+    let result = decode_options::<NobleIdlFormatOptions>(options)
+        .and_then(|options| format::format_source(&options.source));
 
-    let buff = nobleidl_alloc(model.len());
+    let result = match result {
+        Ok(formatted) => NobleIdlFormatResult::Success { formatted },
+        Err(e) => NobleIdlFormatResult::Failure { errors: boxed_errors(e) },
+    };
+
+    export_buffer(serialize_result(result), result_size)
+}
+
+/// Returns the compiler version as a UTF-8 string (not null terminated).
+///
+/// # Safety
+/// `result_size` must be valid for writes.
+/// The returned buffer must be released with `nobleidl_free` using the size written to `result_size`.
+#[no_mangle]
+pub unsafe extern "C" fn nobleidl_version(result_size: *mut usize) -> *mut u8 {
+    export_buffer(env!("CARGO_PKG_VERSION").as_bytes().to_vec(), result_size)
+}
+
+/// Returns the version of the plugin protocol used by this compiler.
+#[no_mangle]
+pub extern "C" fn nobleidl_protocol_version() -> u32 {
+    noble_idl_api::PROTOCOL_VERSION
+}
+
+unsafe fn export_buffer(data: Vec<u8>, result_size: *mut usize) -> *mut u8 {
+    let buff = nobleidl_alloc(data.len());
 
 	if buff.is_null() {
 		return buff;
 	}
 
-    std::ptr::copy(model.as_ptr(), buff, model.len());
+    std::ptr::copy(data.as_ptr(), buff, data.len());
 
-	result_size.write_unaligned(model.len());
+	result_size.write_unaligned(data.len());
 
     buff
 }
@@ -188,12 +601,12 @@ fn compile_model_serialized(options: &[u8]) -> Vec<u8> {
     serialize_result(match compile_model_options_ser(options) {
         Ok(model) => NobleIdlCompileModelResult::Success(Box::new(model)),
         Err(e) => NobleIdlCompileModelResult::Failure {
-            errors: vec![ format!("{:?}", e) ],
+            errors: e.into_compile_errors().iter().map(ToString::to_string).collect(),
         },
     })
 }
 
-fn serialize_result(result: NobleIdlCompileModelResult) -> Vec<u8> {
+fn serialize_result<R: ESExprCodec>(result: R) -> Vec<u8> {
     let result = result.encode_esexpr();
     let mut buff = Vec::new();
 
@@ -203,8 +616,11 @@ fn serialize_result(result: NobleIdlCompileModelResult) -> Vec<u8> {
     buff
 }
 
+fn boxed_errors<PE: std::fmt::Debug, C: FromIterator<Box<NobleIdlCompileError>>>(e: Error<PE>) -> C {
+    e.into_compile_errors().into_iter().map(Box::new).collect()
+}
 
-fn compile_model_options_ser(options: &[u8]) -> Result<NobleIdlModel, Error<CompileModelError>> {
+fn decode_options<O: ESExprCodec>(options: &[u8]) -> Result<O, Error<CompileModelError>> {
     let mut options_vec = esexpr_binary::parse(options)
         .collect::<Result<Vec<_>, _>>()
         .map_err(CompileModelError::ParseError)?;
@@ -217,15 +633,23 @@ fn compile_model_options_ser(options: &[u8]) -> Result<NobleIdlModel, Error<Comp
         Err(CompileModelError::ExtraModulesFound)?
     }
 
-    let options = NobleIdlCompileModelOptions::decode_esexpr(options)
+    let options = O::decode_esexpr(options)
         .map_err(CompileModelError::OptionsDecodeError)?;
 
+    Ok(options)
+}
+
 
-    compile_model(options)
+fn compile_model_options_ser(options: &[u8]) -> Result<NobleIdlModel, Error<CompileModelError>> {
+    compile_model(decode_options(options)?)
 }
 
 
 pub fn compile_model(options: NobleIdlCompileModelOptions) -> Result<NobleIdlModel, Error<CompileModelError>> {
+    Ok(check_sources(options)?.model)
+}
+
+fn check_sources(options: NobleIdlCompileModelOptions) -> Result<CheckedModel, Error<CompileModelError>> {
     let mut model = ModelBuilder::new();
     for file in &options.library_files {
         load_source(&mut model, file, true)?;
@@ -235,23 +659,51 @@ pub fn compile_model(options: NobleIdlCompileModelOptions) -> Result<NobleIdlMod
         load_source(&mut model, file, false)?;
     }
 
-    Ok(model.check()?)
+    check_and_lint(model, &LintConfiguration::default())
 }
 
-fn load_source<PE>(model: &mut model::ModelBuilder, source: &String, is_library: bool) -> Result<(), Error<PE>> {
-
-    let (_, def_file) = parser::definition_file(&source)?;
+pub fn check_model(options: NobleIdlCompileModelOptions) -> NobleIdlCheckResult {
+    match check_sources(options) {
+        Ok(checked) => NobleIdlCheckResult::Success {
+            model: Some(Box::new(checked.model)),
+            warnings: checked.warnings.into_iter()
+                .map(|warning| Box::new(match warning {
+                    CheckWarning::Lint(diagnostic) => lint_compile_error(diagnostic),
+                    warning => NobleIdlCompileError {
+                        kind: NobleIdlCompileErrorKind::CheckError,
+                        message: warning.to_string(),
+                        file: None,
+                        span: None,
+                    },
+                }))
+                .collect(),
+        },
+        Err(e) => NobleIdlCheckResult::Failure { errors: boxed_errors(e) },
+    }
+}
 
-    for def in def_file.definitions {
-        model.add_definition(model::DefinitionInfo {
-            package: def_file.package.clone(),
-            imports: def_file.imports.clone(),
-            def,
-            is_library,
-        })?;
+// Parses every source and reports all parse errors, without building the model.
+pub fn parse_sources(options: &NobleIdlCompileModelOptions) -> NobleIdlCheckResult {
+    let errors = options.library_files.iter()
+        .chain(&options.files)
+        .filter_map(|source| parse_definition_file::<CompileModelError>(source, None).err())
+        .flat_map(Error::into_compile_errors)
+        .map(Box::new)
+        .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        NobleIdlCheckResult::Success { model: None, warnings: Vec::new() }
+    }
+    else {
+        NobleIdlCheckResult::Failure { errors }
     }
+}
 
-    Ok(())
+fn load_source<PE>(model: &mut model::ModelBuilder, source: &str, is_library: bool) -> Result<(), Error<PE>> {
+
+    let def_file = parse_definition_file(source, None)?;
+
+    add_file_definitions(model, &def_file, is_library)
 }
 
 
@@ -0,0 +1,390 @@
+use std::collections::{HashMap, HashSet};
+
+use esexpr::{ESExpr, ESExprCodec};
+use itertools::Itertools;
+use noble_idl_api::{annotation_schema::AnnotationTarget, Annotation, Definition, DefinitionInfo, PackageName, QualifiedName, TypeExpr, TypeParameter};
+
+use crate::{ast::SourceLocation, model::{deprecation::{collect_definition_types, collect_types}, CheckWarning, CheckedModel, FileScope}};
+
+pub const LINT_SCOPE: &str = "lint";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    UnusedImport,
+    NamingConvention,
+    EmptyInterface,
+    RecursiveRecord,
+    ESExprWithoutDeriveCodec,
+    ShadowedType,
+}
+
+impl LintRule {
+    pub const ALL: &'static [LintRule] = &[
+        LintRule::UnusedImport,
+        LintRule::NamingConvention,
+        LintRule::EmptyInterface,
+        LintRule::RecursiveRecord,
+        LintRule::ESExprWithoutDeriveCodec,
+        LintRule::ShadowedType,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LintRule::UnusedImport => "unused-import",
+            LintRule::NamingConvention => "naming-convention",
+            LintRule::EmptyInterface => "empty-interface",
+            LintRule::RecursiveRecord => "recursive-record",
+            LintRule::ESExprWithoutDeriveCodec => "esexpr-without-derive-codec",
+            LintRule::ShadowedType => "shadowed-type",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LintRule> {
+        LintRule::ALL.iter().copied().find(|rule| rule.name() == name)
+    }
+
+    // esexpr annotations on types without derive-codec were rejected before they became a lint.
+    pub fn default_level(self) -> LintLevel {
+        match self {
+            LintRule::ESExprWithoutDeriveCodec => LintLevel::Deny,
+            _ => LintLevel::Warn,
+        }
+    }
+}
+
+impl std::fmt::Display for LintRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(ESExprCodec, Debug, Clone, Copy, PartialEq, Eq)]
+#[simple_enum]
+pub enum LintLevel {
+    #[constructor = "allow"]
+    Allow,
+
+    #[constructor = "warn"]
+    Warn,
+
+    #[constructor = "deny"]
+    Deny,
+}
+
+#[derive(ESExprCodec, Debug, Clone, Default, PartialEq)]
+#[constructor = "lints"]
+pub struct LintConfiguration {
+    #[dict]
+    pub levels: HashMap<String, LintLevel>,
+}
+
+impl LintConfiguration {
+    pub fn level(&self, rule: LintRule) -> LintLevel {
+        self.levels.get(rule.name()).copied().unwrap_or_else(|| rule.default_level())
+    }
+}
+
+#[derive(ESExprCodec, Debug, Clone, PartialEq)]
+pub enum LintAnnotation {
+    #[constructor = "allow"]
+    Allow {
+        #[vararg]
+        rules: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct LintDiagnostic {
+    pub rule: LintRule,
+    pub message: String,
+    pub location: Option<SourceLocation>,
+}
+
+impl std::fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            if let Some(file) = &location.file {
+                write!(f, "{}:", file)?;
+            }
+            write!(f, "{}:{}: ", location.line, location.column)?;
+        }
+        write!(f, "{} [{}]", self.message, self.rule)
+    }
+}
+
+
+pub(crate) fn check_lint_annotation(target: AnnotationTarget, value: &ESExpr) -> Result<(), String> {
+    match target {
+        AnnotationTarget::Package |
+        AnnotationTarget::Record |
+        AnnotationTarget::Enum |
+        AnnotationTarget::SimpleEnum |
+        AnnotationTarget::ExternType |
+        AnnotationTarget::Interface |
+        AnnotationTarget::ExceptionType => {
+            let LintAnnotation::Allow { rules } = LintAnnotation::decode_esexpr(value.clone())
+                .map_err(|e| format!("{:?}", e))?;
+
+            match rules.iter().find(|rule| LintRule::from_name(rule).is_none()) {
+                Some(rule) => Err(format!("Unknown lint rule {}", rule)),
+                None => Ok(()),
+            }
+        },
+
+        AnnotationTarget::RecordField |
+        AnnotationTarget::EnumCase |
+        AnnotationTarget::SimpleEnumCase |
+        AnnotationTarget::InterfaceMethod |
+        AnnotationTarget::MethodParameter |
+        AnnotationTarget::TypeParameter => Err(format!("lints cannot be allowed on a {}", target)),
+    }
+}
+
+// Runs the lint rules over the definitions that are being compiled.
+// Warnings are added to the checked model and denied lints are returned as errors.
+pub fn run(checked: &mut CheckedModel, config: &LintConfiguration) -> Result<(), Vec<LintDiagnostic>> {
+    for rule in config.levels.keys().sorted() {
+        if LintRule::from_name(rule).is_none() {
+            checked.warnings.push(CheckWarning::UnknownLintRule(rule.clone()));
+        }
+    }
+
+    let mut linter = Linter::new(checked, config);
+    linter.lint_files(&checked.files);
+
+    for dfn in &checked.model.definitions {
+        if !dfn.is_library {
+            linter.lint_definition(dfn);
+        }
+    }
+
+    for (name, path) in checked.esexpr_without_derive.iter().sorted().dedup() {
+        let Some(dfn) = linter.definitions.get(name).copied() else { continue; };
+        if dfn.is_library {
+            continue;
+        }
+
+        let location =
+            if path.is_empty() { name.to_string() }
+            else { format!("{} ({})", name, path.join(".")) };
+
+        linter.report(dfn, LintRule::ESExprWithoutDeriveCodec, format!("esexpr annotation on {} is ignored because the type does not derive a codec", location));
+    }
+
+    let diagnostics = linter.diagnostics;
+
+    let mut denied = Vec::new();
+    for diagnostic in diagnostics {
+        match config.level(diagnostic.rule) {
+            LintLevel::Allow => {},
+            LintLevel::Warn => checked.warnings.push(CheckWarning::Lint(diagnostic)),
+            LintLevel::Deny => denied.push(diagnostic),
+        }
+    }
+
+    if denied.is_empty() {
+        Ok(())
+    }
+    else {
+        Err(denied)
+    }
+}
+
+struct Linter<'a> {
+    config: &'a LintConfiguration,
+    definitions: HashMap<&'a QualifiedName, &'a DefinitionInfo>,
+    package_allows: HashMap<&'a PackageName, HashSet<LintRule>>,
+    imports: HashMap<&'a QualifiedName, &'a [PackageName]>,
+    annotation_scope_types: HashMap<(&'a str, AnnotationTarget), &'a TypeExpr>,
+    locations: &'a HashMap<QualifiedName, SourceLocation>,
+    diagnostics: Vec<LintDiagnostic>,
+}
+
+impl <'a> Linter<'a> {
+    fn new(checked: &'a CheckedModel, config: &'a LintConfiguration) -> Self {
+        Linter {
+            config,
+            definitions: checked.model.definitions.iter()
+                .map(|dfn| (dfn.name.as_ref(), dfn.as_ref()))
+                .collect(),
+            package_allows: checked.model.packages.iter()
+                .map(|package| (package.name.as_ref(), allowed_rules(&package.annotations)))
+                .collect(),
+            imports: checked.files.iter()
+                .flat_map(|file| file.definitions.iter().map(|name| (name, file.imports.as_slice())))
+                .collect(),
+            annotation_scope_types: checked.model.annotation_scopes.iter()
+                .map(|scope| ((scope.scope.as_str(), scope.target), scope.value_type.as_ref()))
+                .collect(),
+            locations: &checked.locations,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn is_allowed(&self, package: &PackageName, annotations: &[Box<Annotation>], rule: LintRule) -> bool {
+        self.config.level(rule) == LintLevel::Allow ||
+            self.package_allows.get(package).is_some_and(|allows| allows.contains(&rule)) ||
+            allowed_rules(annotations).contains(&rule)
+    }
+
+    fn report(&mut self, dfn: &DefinitionInfo, rule: LintRule, message: String) {
+        if !self.is_allowed(dfn.name.package_name(), &dfn.annotations, rule) {
+            let location = self.locations.get(&dfn.name).cloned();
+            self.diagnostics.push(LintDiagnostic { rule, message, location });
+        }
+    }
+
+    fn report_package(&mut self, package: &PackageName, location: &SourceLocation, rule: LintRule, message: String) {
+        if !self.is_allowed(package, &[], rule) {
+            self.diagnostics.push(LintDiagnostic { rule, message, location: Some(location.clone()) });
+        }
+    }
+
+    fn lint_files(&mut self, files: &[FileScope]) {
+        let mut seen_packages = HashSet::new();
+
+        for file in files {
+            if file.is_library {
+                continue;
+            }
+
+            if seen_packages.insert(&file.package) {
+                for segment in &file.package.0 {
+                    if !is_kebab_case(segment) {
+                        self.report_package(&file.package, &file.package_location, LintRule::NamingConvention, format!("Package {} is not kebab-case", file.package));
+                        break;
+                    }
+                }
+            }
+
+            self.lint_imports(file);
+        }
+    }
+
+    fn lint_imports(&mut self, file: &FileScope) {
+        let mut used_types = Vec::new();
+        for name in &file.definitions {
+            if let Some(dfn) = self.definitions.get(name) {
+                collect_definition_types(dfn, &mut used_types);
+            }
+        }
+
+        for (scope, target) in &file.annotation_scopes {
+            if let Some(value_type) = self.annotation_scope_types.get(&(scope.as_str(), *target)) {
+                collect_types(value_type, &mut used_types);
+            }
+        }
+
+        let used_packages = used_types.iter()
+            .map(QualifiedName::package_name)
+            .collect::<HashSet<_>>();
+
+        for (import, location) in file.imports.iter().zip(&file.import_locations).unique_by(|(import, _)| *import) {
+            if *import != file.package && !used_packages.contains(import) {
+                self.report_package(&file.package, location, LintRule::UnusedImport, format!("Unused import {} in package {}", import, file.package));
+            }
+        }
+    }
+
+    fn lint_definition(&mut self, dfn: &'a DefinitionInfo) {
+        let mut identifiers = vec![ dfn.name.name() ];
+        identifiers.extend(dfn.type_parameters.iter().map(|tp| tp.name()));
+
+        self.lint_shadowed_types(dfn, &dfn.type_parameters);
+
+        match dfn.definition.as_ref() {
+            Definition::Record(r) => {
+                identifiers.extend(r.fields.iter().map(|field| field.name.as_str()));
+
+                if self.is_recursive_record(dfn.name.as_ref()) {
+                    self.report(dfn, LintRule::RecursiveRecord, format!("Record {} contains itself without an enum indirection", dfn.name));
+                }
+            },
+            Definition::Enum(e) => {
+                for c in &e.cases {
+                    identifiers.push(&c.name);
+                    identifiers.extend(c.fields.iter().map(|field| field.name.as_str()));
+                }
+            },
+            Definition::SimpleEnum(e) => {
+                identifiers.extend(e.cases.iter().map(|c| c.name.as_str()));
+            },
+            Definition::ExternType(_) => {},
+            Definition::Interface(iface) => {
+                if iface.methods.is_empty() {
+                    self.report(dfn, LintRule::EmptyInterface, format!("Interface {} has no methods", dfn.name));
+                }
+
+                for method in &iface.methods {
+                    identifiers.push(&method.name);
+                    identifiers.extend(method.type_parameters.iter().map(|tp| tp.name()));
+                    identifiers.extend(method.parameters.iter().map(|param| param.name.as_str()));
+
+                    self.lint_shadowed_types(dfn, &method.type_parameters);
+                }
+            },
+            Definition::ExceptionType(_) => {},
+        }
+
+        for identifier in identifiers.into_iter().unique() {
+            if !is_kebab_case(identifier) {
+                self.report(dfn, LintRule::NamingConvention, format!("Identifier {} in {} is not kebab-case", identifier, dfn.name));
+            }
+        }
+    }
+
+    fn lint_shadowed_types(&mut self, dfn: &DefinitionInfo, type_parameters: &[Box<TypeParameter>]) {
+        let imports = self.imports.get(dfn.name.as_ref()).copied().unwrap_or_default();
+        let packages = std::iter::once(dfn.name.package_name()).chain(imports).unique().collect_vec();
+
+        for tp in type_parameters {
+            let shadowed = packages.iter()
+                .map(|package| QualifiedName(Box::new((*package).clone()), tp.name().to_owned()))
+                .find(|name| self.definitions.contains_key(name));
+
+            if let Some(shadowed) = shadowed {
+                self.report(dfn, LintRule::ShadowedType, format!("Type parameter {} of {} shadows {}", tp.name(), dfn.name, shadowed));
+            }
+        }
+    }
+
+    // Fields of a record type are stored inline, so a record that reaches itself only through records can never be constructed.
+    fn is_recursive_record(&self, name: &QualifiedName) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = vec![ name ];
+
+        while let Some(current) = pending.pop() {
+            let Some(Definition::Record(r)) = self.definitions.get(current).map(|dfn| dfn.definition.as_ref()) else { continue; };
+
+            for field in &r.fields {
+                let TypeExpr::DefinedType(field_type, _) = field.field_type.as_ref() else { continue; };
+
+                if field_type.as_ref() == name {
+                    return true;
+                }
+
+                if visited.insert(field_type.as_ref()) {
+                    pending.push(field_type);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+fn allowed_rules(annotations: &[Box<Annotation>]) -> HashSet<LintRule> {
+    annotations.iter()
+        .filter(|ann| ann.scope == LINT_SCOPE)
+        .filter_map(|ann| LintAnnotation::decode_esexpr(ann.value.clone()).ok())
+        .flat_map(|ann| match ann {
+            LintAnnotation::Allow { rules } => rules,
+        })
+        .filter_map(|rule| LintRule::from_name(&rule))
+        .collect()
+}
+
+fn is_kebab_case(identifier: &str) -> bool {
+    identifier.starts_with(|c: char| c.is_ascii_lowercase()) &&
+        identifier.split('-').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()))
+}
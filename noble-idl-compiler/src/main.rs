@@ -1,8 +1,14 @@
 use std::{ffi::OsString, path::{Path, PathBuf}};
 
 use clap::Parser;
-use esexpr::ESExpr;
-use noble_idl_compiler::{compile, NobleIDLOptions, ProcessPlugin};
+use noble_idl_api::{NobleIdlDiagnosticSeverity, NobleIdlGenerationResult};
+use noble_idl_compiler::{backends::backend_plugin, check_files_cached, discovery::{discover_files, input_dirs, Excludes}, generate_all, lint::LintConfiguration, project::{find_manifest, load_plugin_configuration, load_project, read_esexpr}, Error, ParseCache, PluginInvocation, ProcessPlugin};
+
+use crate::cli_plugin::{CliPlugin, CliPluginError};
+
+mod cli_plugin;
+#[cfg(not(target_family = "wasm"))]
+mod watch;
 
 #[derive(Debug, Parser)]
 pub struct CommandLineOptions {
@@ -12,42 +18,227 @@ pub struct CommandLineOptions {
     #[arg(short = 'i', long = "input")]
     pub files: Vec<PathBuf>,
 
-    #[arg(short = 'P', long = "plugin-command")]
-    pub plugin_command: OsString,
+    #[arg(short = 'P', long = "plugin-command", group = "plugin", requires = "plugin_options")]
+    pub plugin_command: Option<OsString>,
+
+    #[arg(short = 'b', long = "backend", group = "plugin", requires = "plugin_options")]
+    pub backend: Option<String>,
 
     #[arg(short = 'A', long = "plugin-arguments")]
     pub plugin_arguments: Vec<OsString>,
 
-    #[arg(short = 'c', long = "plugin-options")]
-    pub plugin_options: PathBuf,
+    #[arg(short = 'c', long = "plugin-options", requires = "plugin")]
+    pub plugin_options: Option<PathBuf>,
+
+    #[arg(short = 'x', long = "exclude")]
+    pub excludes: Vec<String>,
+
+    #[arg(long = "plugin-config")]
+    pub plugin_configs: Vec<PathBuf>,
+
+    #[arg(short = 'm', long = "manifest", conflicts_with = "no_manifest")]
+    pub manifest: Option<PathBuf>,
+
+    #[arg(long = "no-manifest")]
+    pub no_manifest: bool,
+
+    #[arg(short = 'w', long = "watch")]
+    pub watch: bool,
 }
 
 
 fn main() {
     let args = CommandLineOptions::parse();
 
-    let plugin_options = read_plugin_options(&args.plugin_options);
+    if args.watch {
+        #[cfg(not(target_family = "wasm"))]
+        watch::watch(&args);
+
+        #[cfg(target_family = "wasm")]
+        {
+            eprintln!("Watch mode is not supported on this platform.");
+            std::process::exit(1);
+        }
+    }
 
-    let proc_plugin = ProcessPlugin {
-        plugin_command: args.plugin_command,
-        plugin_arguments: args.plugin_arguments,
+    let build = match load_build(&args) {
+        Ok(build) => build,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        },
     };
 
-    let options = NobleIDLOptions {
-        library_files: args.library_files,
-        files: args.files,
-        plugin_options,
+    if run_build(&build, &mut ParseCache::default()) {
+        std::process::exit(1);
+    }
+}
+
+
+struct Build {
+    library_files: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    invocations: Vec<PluginInvocation<CliPlugin>>,
+    lints: LintConfiguration,
+    config_files: Vec<PathBuf>,
+    input_dirs: Vec<PathBuf>,
+    excludes: Excludes,
+}
+
+fn load_build(args: &CommandLineOptions) -> Result<Build, String> {
+    let mut config_files = Vec::new();
+    let mut invocations = Vec::new();
+    let mut lints = LintConfiguration::default();
+
+    if let Some(plugin_options) = &args.plugin_options {
+        let plugin = match (&args.plugin_command, &args.backend) {
+            (Some(plugin_command), _) => ProcessPlugin {
+                plugin_command: plugin_command.clone(),
+                plugin_arguments: args.plugin_arguments.clone(),
+            },
+            (None, Some(backend)) => backend_plugin(backend, args.plugin_arguments.clone())
+                .map_err(|e| format!("Could not find backend: {:?}", e))?,
+            (None, None) => return Err("Plugin options were specified without a plugin.".to_owned()),
+        };
+
+        invocations.push(PluginInvocation {
+            name: args.backend.clone().unwrap_or_else(|| "default".to_owned()),
+            plugin,
+            plugin_options: read_esexpr(plugin_options)
+                .map_err(|e| format!("Could not read plugin options: {:?}", e))?,
+            output_dir: None,
+        });
+        config_files.push(plugin_options.clone());
+    }
+
+    for config_path in &args.plugin_configs {
+        invocations.push(
+            load_plugin_configuration(config_path, &mut config_files)
+                .map_err(|e| format!("Could not load plugin configuration: {:?}", e))?
+        );
+    }
+
+    let mut excludes = Excludes::new(Path::new(""), &args.excludes)
+        .map_err(|e| format!("Invalid exclude pattern: {:?}", e))?;
+    let mut project_excludes = Excludes::default();
+    let mut library_files = discover_files(Path::new(""), &args.library_files, &excludes)
+        .map_err(|e| format!("Could not find library files: {:?}", e))?;
+    let mut files = discover_files(Path::new(""), &args.files, &excludes)
+        .map_err(|e| format!("Could not find input files: {:?}", e))?;
+    let mut dirs = input_dirs(Path::new(""), &args.library_files);
+    dirs.extend(input_dirs(Path::new(""), &args.files));
+
+    if let Some(manifest_path) = manifest_path(args) {
+        let project = load_project(&manifest_path)
+            .map_err(|e| format!("Could not load project manifest: {:?}", e))?;
+
+        if library_files.is_empty() {
+            library_files = project.library_files;
+            dirs.extend(project.library_dirs);
+        }
+
+        if files.is_empty() {
+            files = project.files;
+            dirs.extend(project.input_dirs);
+        }
+
+        if invocations.is_empty() {
+            invocations = project.plugins;
+        }
+
+        lints = project.lints;
+        config_files.extend(project.config_files);
+        project_excludes = project.excludes;
+    }
+
+    library_files.retain(|file| !excludes.is_excluded(file));
+    files.retain(|file| !excludes.is_excluded(file));
+
+    // Files excluded by the manifest are also ignored when watching for changes.
+    excludes.extend(&project_excludes);
+
+    if invocations.is_empty() {
+        return Err("No plugins were specified.".to_owned());
+    }
+
+    let invocations = invocations.into_iter()
+        .map(CliPlugin::load_invocation)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Build {
+        library_files,
+        files,
+        invocations,
+        lints,
+        config_files,
+        input_dirs: dirs,
+        excludes,
+    })
+}
+
+fn run_build(build: &Build, cache: &mut ParseCache) -> bool {
+    let checked = match check_files_cached::<CliPluginError>(cache, &build.library_files, &build.files, &build.lints) {
+        Ok(checked) => checked,
+        Err(Error::LintError(diagnostics)) => {
+            for diagnostic in &diagnostics {
+                eprintln!("error: {}", diagnostic);
+            }
+            return true;
+        },
+        Err(e) => {
+            eprintln!("Error compiling: {:?}", e);
+            return true;
+        },
     };
 
-    let result = compile(&proc_plugin, &options).expect("Error compiling");
+    for warning in &checked.warnings {
+        eprintln!("warning: {}", warning);
+    }
+
+    let results = generate_all(&checked.model, &build.invocations);
+
+    let mut failed = false;
+    for (invocation, result) in build.invocations.iter().zip(results) {
+        match result {
+            Ok(result) => failed |= print_result(&invocation.name, &result),
+            Err(e) => {
+                eprintln!("[{}] Error running plugin: {:?}", invocation.name, e);
+                failed = true;
+            },
+        }
+    }
+
+    failed
+}
 
-    println!("Generated {} files:", result.generated_files.len());
+fn print_result(name: &str, result: &NobleIdlGenerationResult) -> bool {
+    for diagnostic in &result.diagnostics {
+        let severity = match diagnostic.severity {
+            NobleIdlDiagnosticSeverity::Error => "error",
+            NobleIdlDiagnosticSeverity::Warning => "warning",
+            NobleIdlDiagnosticSeverity::Note => "note",
+        };
+        eprintln!("[{}] {}: {}", name, severity, diagnostic.message);
+    }
+
+    println!("[{}] Generated {} files:", name, result.generated_files.len());
     for file in &result.generated_files {
         println!("{}", file);
     }
+
+    result.diagnostics.iter().any(|d| d.severity == NobleIdlDiagnosticSeverity::Error)
 }
 
-fn read_plugin_options(path: &Path) -> ESExpr {
-    let s = std::fs::read_to_string(path).expect("Could not read plugin options.");
-    esexpr_text::parse(&s).ok().expect("Could not parse plugin options.")
+fn manifest_path(args: &CommandLineOptions) -> Option<PathBuf> {
+    if args.no_manifest {
+        return None;
+    }
+
+    match &args.manifest {
+        Some(manifest_path) => Some(manifest_path.clone()),
+        None => {
+            let current_dir = std::env::current_dir().ok()?;
+            find_manifest(&current_dir)
+        },
+    }
 }
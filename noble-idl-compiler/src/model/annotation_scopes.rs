@@ -0,0 +1,251 @@
+use std::{borrow::BorrowMut, collections::{HashMap, HashSet}};
+
+use esexpr::{ESExpr, ESExprCodec};
+use itertools::Itertools;
+use noble_idl_api::annotation_schema::{AnnotationLocation, AnnotationProblem, AnnotationRegistry, AnnotationTarget};
+use noble_idl_api::*;
+
+use crate::lint::{check_lint_annotation, LINT_SCOPE};
+
+use super::{phase5, tag_scanner::TagScannerState, AnnotationScopeEntry, CheckError, CheckWarning};
+
+// Scopes that are read by backends outside of the compiler.
+const BACKEND_SCOPES: &[&str] = &["rust", "java", "scala", "csharp"];
+
+pub fn builtin_registry() -> AnnotationRegistry {
+	let mut registry = AnnotationRegistry::new();
+	registry.register("esexpr", check_esexpr_annotation);
+	registry.register("deprecated", check_deprecated_annotation);
+	registry.register(LINT_SCOPE, check_lint_annotation);
+
+	for scope in BACKEND_SCOPES {
+		registry.declare(*scope);
+	}
+
+	registry
+}
+
+fn check_esexpr_annotation(target: AnnotationTarget, value: &ESExpr) -> Result<(), String> {
+	fn decode<T: ESExprCodec>(value: &ESExpr) -> Result<(), String> {
+		T::decode_esexpr(value.clone())
+			.map(|_| ())
+			.map_err(|e| format!("{:?}", e))
+	}
+
+	match target {
+		AnnotationTarget::Package => decode::<EsexprAnnPackage>(value),
+		AnnotationTarget::Record => decode::<EsexprAnnRecord>(value),
+		AnnotationTarget::RecordField => decode::<EsexprAnnRecordField>(value),
+		AnnotationTarget::Enum => decode::<EsexprAnnEnum>(value),
+		AnnotationTarget::EnumCase => decode::<EsexprAnnEnumCase>(value),
+		AnnotationTarget::SimpleEnum => decode::<EsexprAnnSimpleEnum>(value),
+		AnnotationTarget::SimpleEnumCase => decode::<EsexprAnnSimpleEnumCase>(value),
+		AnnotationTarget::ExternType => decode::<EsexprAnnExternType>(value),
+		AnnotationTarget::Interface |
+		AnnotationTarget::InterfaceMethod |
+		AnnotationTarget::MethodParameter |
+		AnnotationTarget::ExceptionType |
+		AnnotationTarget::TypeParameter => Err(format!("esexpr annotations are not supported on a {}", target)),
+	}
+}
+
+fn check_deprecated_annotation(target: AnnotationTarget, value: &ESExpr) -> Result<(), String> {
+	match target {
+		AnnotationTarget::Record |
+		AnnotationTarget::RecordField |
+		AnnotationTarget::Enum |
+		AnnotationTarget::EnumCase |
+		AnnotationTarget::SimpleEnum |
+		AnnotationTarget::SimpleEnumCase |
+		AnnotationTarget::ExternType |
+		AnnotationTarget::Interface |
+		AnnotationTarget::InterfaceMethod |
+		AnnotationTarget::ExceptionType => {
+			Deprecation::decode_esexpr(value.clone())
+				.map(|_| ())
+				.map_err(|e| format!("{:?}", e))
+		},
+
+		AnnotationTarget::Package |
+		AnnotationTarget::MethodParameter |
+		AnnotationTarget::TypeParameter => Err(format!("a {} cannot be deprecated", target)),
+	}
+}
+
+pub fn run(definitions: &HashMap<QualifiedName, DefinitionInfo>, package_annotations: &HashMap<PackageName, Vec<Annotation>>, registry: &AnnotationRegistry, warnings: &mut Vec<CheckWarning>) -> Result<(), CheckError> {
+	let mut problems = Vec::new();
+
+	for (name, annotations) in package_annotations.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+		registry.check_package(name, annotations, &mut problems);
+	}
+
+	for (_, dfn) in definitions.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+		registry.check_definition(dfn, &mut problems);
+	}
+
+	for problem in problems {
+		match problem {
+			AnnotationProblem::UnknownScope(location, scope) => warnings.push(CheckWarning::UnknownAnnotationScope(location, scope)),
+			AnnotationProblem::InvalidValue { location, scope, message } => return Err(CheckError::InvalidAnnotation(location, scope, message)),
+		}
+	}
+
+	Ok(())
+}
+
+pub fn declarations(entries: Vec<AnnotationScopeEntry>) -> Result<Vec<AnnotationScopeInfo>, CheckError> {
+	let mut seen = HashSet::new();
+
+	entries.into_iter()
+		.map(|entry| {
+			let decl = entry.declaration;
+			if !seen.insert((decl.scope.clone(), decl.target)) {
+				return Err(CheckError::DuplicateAnnotationScope(decl.scope, decl.target));
+			}
+
+			Ok(AnnotationScopeInfo {
+				scope: decl.scope,
+				target: decl.target,
+				value_type: Box::new(decl.value_type.into_api()),
+			})
+		})
+		.collect()
+}
+
+// Decodes the annotations of every scope declared in the model and stores the decoded values.
+// This must run after the esexpr options and default values are known.
+pub fn decode(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, package_annotations: &mut HashMap<PackageName, Vec<Annotation>>, annotation_scopes: &[AnnotationScopeInfo], tag_scan_state: &mut TagScannerState) -> Result<(), CheckError> {
+	if annotation_scopes.is_empty() {
+		return Ok(());
+	}
+
+	for decl in annotation_scopes {
+		if !has_codec(definitions, &decl.value_type) {
+			return Err(CheckError::AnnotationScopeCodecMissing(decl.scope.clone(), decl.target));
+		}
+	}
+
+	// The annotations are updated in place, so values are decoded using a copy of the definitions.
+	let lookup = definitions.clone();
+
+	let mut decoder = AnnotationDecoder {
+		definitions: &lookup,
+		tag_scan_state,
+		value_types: annotation_scopes.iter()
+			.map(|decl| ((decl.scope.as_str(), decl.target), decl.value_type.as_ref()))
+			.collect(),
+	};
+
+	for (name, annotations) in package_annotations.iter_mut().sorted_by(|a, b| a.0.cmp(b.0)) {
+		decoder.decode_annotations(&AnnotationLocation::Package(name.clone()), AnnotationTarget::Package, annotations)?;
+	}
+
+	for (_, dfn) in definitions.iter_mut().sorted_by(|a, b| a.0.cmp(b.0)) {
+		decoder.decode_definition(dfn)?;
+	}
+
+	Ok(())
+}
+
+fn has_codec(definitions: &HashMap<QualifiedName, DefinitionInfo>, t: &TypeExpr) -> bool {
+	let TypeExpr::DefinedType(name, _) = t else { return false; };
+	let Some(dfn) = definitions.get(name) else { return false; };
+
+	match dfn.definition.as_ref() {
+		Definition::Record(r) => r.esexpr_options.is_some(),
+		Definition::Enum(e) => e.esexpr_options.is_some(),
+		Definition::SimpleEnum(e) => e.esexpr_options.is_some(),
+		Definition::ExternType(et) => et.esexpr_options.is_some(),
+		Definition::Interface(_) => false,
+		Definition::ExceptionType(_) => false,
+	}
+}
+
+struct AnnotationDecoder<'a> {
+	definitions: &'a HashMap<QualifiedName, DefinitionInfo>,
+	tag_scan_state: &'a mut TagScannerState,
+	value_types: HashMap<(&'a str, AnnotationTarget), &'a TypeExpr>,
+}
+
+impl <'a> AnnotationDecoder<'a> {
+	fn decode_definition(&mut self, dfn: &mut DefinitionInfo) -> Result<(), CheckError> {
+		let name = dfn.name.as_ref().clone();
+		let location = |path: &[&str]| AnnotationLocation::Definition(
+			name.clone(),
+			path.iter().map(|segment| (*segment).to_owned()).collect(),
+		);
+
+		self.decode_annotations(&location(&[]), AnnotationTarget::for_definition(&dfn.definition), &mut dfn.annotations)?;
+		self.decode_type_parameters(&location, &[], &mut dfn.type_parameters)?;
+
+		match dfn.definition.as_mut() {
+			Definition::Record(rec) => self.decode_fields(&location, &[], &mut rec.fields)?,
+			Definition::Enum(e) => {
+				for c in &mut e.cases {
+					self.decode_annotations(&location(&[&c.name]), AnnotationTarget::EnumCase, &mut c.annotations)?;
+					self.decode_fields(&location, &[&c.name], &mut c.fields)?;
+				}
+			},
+			Definition::SimpleEnum(e) => {
+				for c in &mut e.cases {
+					self.decode_annotations(&location(&[&c.name]), AnnotationTarget::SimpleEnumCase, &mut c.annotations)?;
+				}
+			},
+			Definition::ExternType(_) => {},
+			Definition::Interface(iface) => {
+				for method in &mut iface.methods {
+					self.decode_annotations(&location(&[&method.name]), AnnotationTarget::InterfaceMethod, &mut method.annotations)?;
+					self.decode_type_parameters(&location, &[&method.name], &mut method.type_parameters)?;
+
+					for param in &mut method.parameters {
+						self.decode_annotations(&location(&[&method.name, &param.name]), AnnotationTarget::MethodParameter, &mut param.annotations)?;
+					}
+				}
+			},
+			Definition::ExceptionType(_) => {},
+		}
+
+		Ok(())
+	}
+
+	fn decode_fields(&mut self, location: &impl Fn(&[&str]) -> AnnotationLocation, path: &[&str], fields: &mut [Box<RecordField>]) -> Result<(), CheckError> {
+		for field in fields {
+			let mut field_path = path.to_vec();
+			field_path.push(&field.name);
+			self.decode_annotations(&location(&field_path), AnnotationTarget::RecordField, &mut field.annotations)?;
+		}
+
+		Ok(())
+	}
+
+	fn decode_type_parameters(&mut self, location: &impl Fn(&[&str]) -> AnnotationLocation, path: &[&str], type_parameters: &mut [Box<TypeParameter>]) -> Result<(), CheckError> {
+		for tp in type_parameters {
+			let mut tp_path = path.to_vec();
+			tp_path.push(tp.name());
+			let tp_location = location(&tp_path);
+			self.decode_annotations(&tp_location, AnnotationTarget::TypeParameter, tp.annotations_mut())?;
+		}
+
+		Ok(())
+	}
+
+	fn decode_annotations<A: BorrowMut<Annotation>>(&mut self, location: &AnnotationLocation, target: AnnotationTarget, annotations: &mut [A]) -> Result<(), CheckError> {
+		for ann in annotations {
+			let ann = ann.borrow_mut();
+
+			let scope_declared = self.value_types.keys().any(|(scope, _)| *scope == ann.scope);
+			if !scope_declared {
+				continue;
+			}
+
+			let Some(value_type) = self.value_types.get(&(ann.scope.as_str(), target)) else {
+				return Err(CheckError::InvalidAnnotation(location.clone(), ann.scope.clone(), format!("{} annotations are not declared for a {}", ann.scope, target)));
+			};
+
+			let decoded = phase5::decode_annotation_value(self.definitions, self.tag_scan_state, location, &ann.scope, value_type, ann.value.clone())?;
+			ann.decoded_value = Some(Box::new(decoded));
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use esexpr::ESExprCodec;
+use itertools::Itertools;
+use noble_idl_api::*;
+
+use super::CheckWarning;
+
+const DEPRECATED_SCOPE: &str = "deprecated";
+
+// Moves deprecated annotations into the deprecated fields of the model
+// and warns when a definition that is being compiled uses a deprecated type.
+pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, warnings: &mut Vec<CheckWarning>) {
+	for dfn in definitions.values_mut() {
+		extract_definition(dfn);
+	}
+
+	for (_, dfn) in definitions.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+		if dfn.is_library || dfn.deprecated.is_some() {
+			continue;
+		}
+
+		let mut used_types = Vec::new();
+		collect_definition_types(dfn, &mut used_types);
+
+		for name in used_types {
+			let Some(used_dfn) = definitions.get(&name) else { continue; };
+			let Some(deprecation) = used_dfn.deprecated.as_ref() else { continue; };
+
+			warnings.push(CheckWarning::DeprecatedTypeUsed(
+				dfn.name.as_ref().clone(),
+				name,
+				deprecation.message.clone(),
+			));
+		}
+	}
+}
+
+fn take_deprecation<A: AsRef<Annotation>>(annotations: &mut Vec<A>) -> Option<Box<Deprecation>> {
+	let mut deprecation = None;
+
+	annotations.retain(|ann| {
+		let ann = ann.as_ref();
+		if ann.scope != DEPRECATED_SCOPE {
+			return true;
+		}
+
+		// The value was already validated when annotation scopes were checked.
+		if let Ok(value) = Deprecation::decode_esexpr(ann.value.clone()) {
+			deprecation = Some(Box::new(value));
+		}
+
+		false
+	});
+
+	deprecation
+}
+
+fn extract_definition(dfn: &mut DefinitionInfo) {
+	dfn.deprecated = take_deprecation(&mut dfn.annotations);
+
+	match dfn.definition.as_mut() {
+		Definition::Record(r) => extract_fields(&mut r.fields),
+		Definition::Enum(e) => {
+			for c in &mut e.cases {
+				c.deprecated = take_deprecation(&mut c.annotations);
+				extract_fields(&mut c.fields);
+			}
+		},
+		Definition::SimpleEnum(e) => {
+			for c in &mut e.cases {
+				c.deprecated = take_deprecation(&mut c.annotations);
+			}
+		},
+		Definition::ExternType(_) => {},
+		Definition::Interface(iface) => {
+			for method in &mut iface.methods {
+				method.deprecated = take_deprecation(&mut method.annotations);
+			}
+		},
+		Definition::ExceptionType(_) => {},
+	}
+}
+
+fn extract_fields(fields: &mut [Box<RecordField>]) {
+	for field in fields {
+		field.deprecated = take_deprecation(&mut field.annotations);
+	}
+}
+
+pub(crate) fn collect_definition_types(dfn: &DefinitionInfo, used_types: &mut Vec<QualifiedName>) {
+	match dfn.definition.as_ref() {
+		Definition::Record(r) => {
+			for field in &r.fields {
+				collect_types(&field.field_type, used_types);
+			}
+		},
+		Definition::Enum(e) => {
+			for field in e.cases.iter().flat_map(|c| &c.fields) {
+				collect_types(&field.field_type, used_types);
+			}
+		},
+		Definition::SimpleEnum(_) => {},
+		Definition::ExternType(_) => {},
+		Definition::Interface(iface) => {
+			for method in &iface.methods {
+				for param in &method.parameters {
+					collect_types(&param.parameter_type, used_types);
+				}
+
+				collect_types(&method.return_type, used_types);
+
+				if let Some(throws) = &method.throws {
+					collect_types(throws, used_types);
+				}
+			}
+		},
+		Definition::ExceptionType(ex) => collect_types(&ex.information, used_types),
+	}
+}
+
+pub(crate) fn collect_types(t: &TypeExpr, used_types: &mut Vec<QualifiedName>) {
+	match t {
+		TypeExpr::DefinedType(name, args) => {
+			if !used_types.contains(name) {
+				used_types.push(name.as_ref().clone());
+			}
+
+			for arg in args {
+				collect_types(arg, used_types);
+			}
+		},
+		TypeExpr::TypeParameter { .. } => {},
+	}
+}
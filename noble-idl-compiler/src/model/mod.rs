@@ -1,9 +1,11 @@
 use std::collections::{hash_map, HashMap, HashSet};
 use esexpr::{DecodeError, ESExprTag};
 use itertools::Itertools;
-use noble_idl_api::NobleIdlModel;
+use noble_idl_api::{annotation_schema::{AnnotationLocation, AnnotationRegistry, AnnotationTarget}, NobleIdlModel, PackageInfo};
 use tag_scanner::TagScannerState;
 
+mod annotation_scopes;
+pub(crate) mod deprecation;
 mod tag_scanner;
 
 mod phase1; // Phase 1 - Type resolution
@@ -15,6 +17,7 @@ mod phase6; // Phase 6 - Check type codecs
 mod phase7; // Phase 7 - Remove annotations
 
 use crate::ast::*;
+use crate::lint::LintDiagnostic;
 
 #[derive(Debug)]
 pub enum CheckError {
@@ -28,11 +31,18 @@ pub enum CheckError {
     TypeInMultiplePackages(String, Vec<PackageName>),
 
 	InvalidExceptionType(noble_idl_api::TypeExpr),
+	InternalTypeExposed(QualifiedName, QualifiedName),
+	InternalTypeNotAccessible(QualifiedName, QualifiedName),
 
     TypeParameterMismatch { expected: usize, actual: usize, },
 
 
+	InvalidAnnotation(AnnotationLocation, String, String),
+	DuplicateAnnotationScope(String, AnnotationTarget),
+	AnnotationScopeCodecMissing(String, AnnotationTarget),
 	InvalidESExprAnnotation(QualifiedName, DecodeError),
+	InvalidPackageESExprAnnotation(PackageName, DecodeError),
+	DuplicatePackageESExprAnnotation(PackageName, String),
 	DuplicateESExprAnnotation(QualifiedName, Vec<String>, String),
 	ESExprAnnotationWithoutDerive(QualifiedName, Vec<String>),
 	ESExprExternTypeCodecMissing(QualifiedName),
@@ -58,11 +68,170 @@ pub enum CheckError {
 }
 
 
+impl CheckError {
+    // The definition that the error was found in, if the error belongs to a single definition.
+    pub fn definition_name(&self) -> Option<&QualifiedName> {
+        match self {
+            CheckError::DuplicateRecordField(name, _, _) |
+            CheckError::DuplicateEnumCase(name, _) |
+            CheckError::DuplicateMethod(name, _) |
+            CheckError::DuplicateMethodParameter(name, _, _) |
+            CheckError::DuplicateTypeParameter(name, _, _) |
+            CheckError::DuplicateDefinition(name) |
+            CheckError::InternalTypeExposed(name, _) |
+            CheckError::InternalTypeNotAccessible(name, _) |
+            CheckError::InvalidAnnotation(AnnotationLocation::Definition(name, _), _, _) |
+            CheckError::InvalidESExprAnnotation(name, _) |
+            CheckError::DuplicateESExprAnnotation(name, _, _) |
+            CheckError::ESExprAnnotationWithoutDerive(name, _) |
+            CheckError::ESExprExternTypeCodecMissing(name) |
+            CheckError::ESExprMemberCodecMissing(name, _, _) |
+            CheckError::ESExprDuplicateTag(name, _) |
+            CheckError::ESExprInlineValueNotSingleField(name, _) |
+            CheckError::ESExprInlineValueInvalidTags(name, _) |
+            CheckError::ESExprEnumCaseIncompatibleOptions(name, _) |
+            CheckError::ESExprFieldIncompatibleOptions(name, _, _) |
+            CheckError::ESExprDictBeforeKeyword(name, _, _) |
+            CheckError::ESExprVarargBeforePositional(name, _, _) |
+            CheckError::ESExprMultipleDict(name, _, _) |
+            CheckError::ESExprMultipleVararg(name, _, _) |
+            CheckError::ESExprVarargAfterOptionalPositional(name, _, _) |
+            CheckError::ESExprMultipleOptionalPositional(name, _, _) |
+            CheckError::ESExprDuplicateKeyword(name, _, _) |
+            CheckError::ESExprInvalidDefaultValue(_, name, _, _) |
+            CheckError::ESExprBuildLiteralFromCodecMissing(name) |
+            CheckError::ESExprInvalidOptionalFieldType(name, _, _) |
+            CheckError::ESExprInvalidDictFieldType(name, _, _) |
+            CheckError::ESExprInvalidVarargFieldType(name, _, _) |
+            CheckError::ESExprInvalidElementType(name) => Some(name),
+
+            CheckError::UnknownType(_) |
+            CheckError::TypeInMultiplePackages(_, _) |
+            CheckError::InvalidExceptionType(_) |
+            CheckError::TypeParameterMismatch { .. } |
+            CheckError::InvalidAnnotation(AnnotationLocation::Package(_), _, _) |
+            CheckError::DuplicateAnnotationScope(_, _) |
+            CheckError::AnnotationScopeCodecMissing(_, _) |
+            CheckError::InvalidPackageESExprAnnotation(_, _) |
+            CheckError::DuplicatePackageESExprAnnotation(_, _) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckError::UnknownType(name) => write!(f, "Unknown type {}", name),
+            CheckError::DuplicateRecordField(name, case, field) => write!(f, "Duplicate field {}", Member(name, case, field)),
+            CheckError::DuplicateEnumCase(name, case) => write!(f, "Duplicate enum case {}.{}", name, case),
+            CheckError::DuplicateMethod(name, method) => write!(f, "Duplicate method {}.{}", name, method),
+            CheckError::DuplicateMethodParameter(name, method, param) => write!(f, "Duplicate parameter {} of method {}.{}", param, name, method),
+            CheckError::DuplicateTypeParameter(name, Some(method), param) => write!(f, "Duplicate type parameter {} of method {}.{}", param, name, method),
+            CheckError::DuplicateTypeParameter(name, None, param) => write!(f, "Duplicate type parameter {} of {}", param, name),
+            CheckError::DuplicateDefinition(name) => write!(f, "Duplicate definition {}", name),
+            CheckError::TypeInMultiplePackages(name, packages) => write!(f, "Type {} is imported from multiple packages: {}", name, packages.iter().join(", ")),
+            CheckError::InvalidExceptionType(t) => write!(f, "Invalid exception type {:?}", t),
+            CheckError::InternalTypeExposed(name, internal) => write!(f, "Public definition {} exposes internal type {}", name, internal),
+            CheckError::InternalTypeNotAccessible(name, internal) => write!(f, "Internal type {} is not accessible from {}", internal, name),
+            CheckError::TypeParameterMismatch { expected, actual } => write!(f, "Expected {} type arguments, got {}", expected, actual),
+            CheckError::InvalidAnnotation(location, scope, message) => write!(f, "Invalid {} annotation on {}: {}", scope, location, message),
+            CheckError::DuplicateAnnotationScope(scope, target) => write!(f, "Annotation scope {} is declared more than once for {}", scope, target),
+            CheckError::AnnotationScopeCodecMissing(scope, target) => write!(f, "The value type of annotation scope {} for {} does not have an ESExpr codec", scope, target),
+            CheckError::InvalidESExprAnnotation(name, e) => write!(f, "Invalid esexpr annotation on {}: {:?}", name, e),
+            CheckError::InvalidPackageESExprAnnotation(package, e) => write!(f, "Invalid esexpr annotation on package {}: {:?}", package, e),
+            CheckError::DuplicatePackageESExprAnnotation(package, ann) => write!(f, "Duplicate esexpr annotation {} on package {}", ann, package),
+            CheckError::DuplicateESExprAnnotation(name, path, ann) => {
+                write!(f, "Duplicate esexpr annotation {} on {}", ann, name)?;
+                for segment in path {
+                    write!(f, ".{}", segment)?;
+                }
+                Ok(())
+            },
+            CheckError::ESExprAnnotationWithoutDerive(name, path) => {
+                write!(f, "esexpr annotation on {}", name)?;
+                for segment in path {
+                    write!(f, ".{}", segment)?;
+                }
+                write!(f, " requires the type to derive a codec")
+            },
+            CheckError::ESExprExternTypeCodecMissing(name) => write!(f, "Extern type {} does not declare an ESExpr codec", name),
+            CheckError::ESExprMemberCodecMissing(name, case, field) => write!(f, "The type of {} does not have an ESExpr codec", Member(name, case, field)),
+            CheckError::ESExprDuplicateTag(name, tag) => write!(f, "{} has multiple cases with the tag {:?}", name, tag),
+            CheckError::ESExprInlineValueNotSingleField(name, case) => write!(f, "Inline value case {}.{} must have exactly one field", name, case),
+            CheckError::ESExprInlineValueInvalidTags(name, case) => write!(f, "The tags of inline value case {}.{} could not be determined", name, case),
+            CheckError::ESExprEnumCaseIncompatibleOptions(name, case) => write!(f, "Incompatible esexpr options on enum case {}.{}", name, case),
+            CheckError::ESExprFieldIncompatibleOptions(name, case, field) => write!(f, "Incompatible esexpr options on field {}", Member(name, case, field)),
+            CheckError::ESExprDictBeforeKeyword(name, case, field) => write!(f, "Dict field {} must come after the keyword fields", Member(name, case, field)),
+            CheckError::ESExprVarargBeforePositional(name, case, field) => write!(f, "Vararg field {} must come after the positional fields", Member(name, case, field)),
+            CheckError::ESExprMultipleDict(name, case, field) => write!(f, "Field {} is a second dict field", Member(name, case, field)),
+            CheckError::ESExprMultipleVararg(name, case, field) => write!(f, "Field {} is a second vararg field", Member(name, case, field)),
+            CheckError::ESExprVarargAfterOptionalPositional(name, case, field) => write!(f, "Vararg field {} follows an optional positional field", Member(name, case, field)),
+            CheckError::ESExprMultipleOptionalPositional(name, case, field) => write!(f, "Field {} is a second optional positional field", Member(name, case, field)),
+            CheckError::ESExprDuplicateKeyword(name, case, field) => write!(f, "Field {} reuses a keyword", Member(name, case, field)),
+            CheckError::ESExprInvalidDefaultValue(message, name, case, field) => write!(f, "Invalid default value for field {}: {}", Member(name, case, field), message),
+            CheckError::ESExprBuildLiteralFromCodecMissing(name) => write!(f, "The type that literals of {} are built from does not have an ESExpr codec", name),
+            CheckError::ESExprInvalidOptionalFieldType(name, case, field) => write!(f, "Optional field {} does not have an optional type", Member(name, case, field)),
+            CheckError::ESExprInvalidDictFieldType(name, case, field) => write!(f, "Dict field {} does not have a dict type", Member(name, case, field)),
+            CheckError::ESExprInvalidVarargFieldType(name, case, field) => write!(f, "Vararg field {} does not have a vararg type", Member(name, case, field)),
+            CheckError::ESExprInvalidElementType(name) => write!(f, "Invalid element type for {}", name),
+        }
+    }
+}
+
+// A field or other member of a definition, optionally within an enum case.
+struct Member<'a>(&'a QualifiedName, &'a Option<String>, &'a str);
+
+impl std::fmt::Display for Member<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)?;
+        if let Some(case) = self.1 {
+            write!(f, ".{}", case)?;
+        }
+        write!(f, ".{}", self.2)
+    }
+}
+
+
+#[derive(Debug)]
+pub enum CheckWarning {
+    UnknownAnnotationScope(AnnotationLocation, String),
+    DeprecatedTypeUsed(QualifiedName, QualifiedName, Option<String>),
+    UnknownLintRule(String),
+    Lint(LintDiagnostic),
+}
+
+impl std::fmt::Display for CheckWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckWarning::UnknownAnnotationScope(location, scope) => write!(f, "Unknown annotation scope {} on {}", scope, location),
+            CheckWarning::DeprecatedTypeUsed(dfn, used_type, message) => {
+                write!(f, "{} uses deprecated type {}", dfn, used_type)?;
+                if let Some(message) = message {
+                    write!(f, ": {}", message)?;
+                }
+                Ok(())
+            },
+            CheckWarning::UnknownLintRule(rule) => write!(f, "Unknown lint rule {}", rule),
+            CheckWarning::Lint(diagnostic) => write!(f, "{}", diagnostic),
+        }
+    }
+}
+
+pub struct CheckedModel {
+    pub model: NobleIdlModel,
+    pub warnings: Vec<CheckWarning>,
+    pub(crate) files: Vec<FileScope>,
+    pub(crate) esexpr_without_derive: Vec<(QualifiedName, Vec<String>)>,
+    pub(crate) locations: HashMap<QualifiedName, SourceLocation>,
+}
+
+
 pub(crate) struct DefinitionInfo {
     pub package: PackageName,
     pub imports: Vec<PackageName>,
     pub def: Definition,
     pub is_library: bool,
+    pub location: SourceLocation,
 }
 
 impl DefinitionInfo {
@@ -82,23 +251,70 @@ impl DefinitionInfo {
     }
 }
 
+// The names visible to the definitions of a single source file.
+pub(crate) struct FileScope {
+    pub package: PackageName,
+    pub package_location: SourceLocation,
+    pub imports: Vec<PackageName>,
+    pub import_locations: Vec<SourceLocation>,
+    pub definitions: Vec<QualifiedName>,
+    pub annotation_scopes: Vec<(String, AnnotationTarget)>,
+    pub is_library: bool,
+}
+
+pub(crate) struct AnnotationScopeEntry {
+    pub package: PackageName,
+    pub imports: Vec<PackageName>,
+    pub declaration: AnnotationScopeDeclaration,
+}
+
 pub(crate) struct ModelBuilder {
     definitions: HashMap<QualifiedName, DefinitionInfo>,
+    package_annotations: HashMap<PackageName, Vec<Annotation>>,
+    annotation_scopes: Vec<AnnotationScopeEntry>,
+    annotation_registry: AnnotationRegistry,
+    files: Vec<FileScope>,
+    locations: HashMap<QualifiedName, SourceLocation>,
 }
 
 impl ModelBuilder {
     pub fn new() -> Self {
         ModelBuilder {
             definitions: HashMap::new(),
+            package_annotations: HashMap::new(),
+            annotation_scopes: Vec::new(),
+            annotation_registry: annotation_scopes::builtin_registry(),
+            files: Vec::new(),
+            locations: HashMap::new(),
         }
     }
 
+    // Annotations from every file that declares the package are combined.
+    pub(crate) fn add_package_annotations(&mut self, package: &PackageName, annotations: &[Annotation]) {
+        if annotations.is_empty() {
+            return;
+        }
+
+        self.package_annotations.entry(package.clone())
+            .or_default()
+            .extend(annotations.iter().cloned());
+    }
+
+    pub(crate) fn add_annotation_scope(&mut self, entry: AnnotationScopeEntry) {
+        self.annotation_scopes.push(entry);
+    }
+
+    pub(crate) fn add_file_scope(&mut self, file: FileScope) {
+        self.files.push(file);
+    }
+
     pub(crate) fn add_definition(&mut self, def: DefinitionInfo) -> Result<(), CheckError> {
         let name = def.qualified_name();
 
         match self.definitions.entry(name) {
             hash_map::Entry::Occupied(_) => return Err(CheckError::DuplicateDefinition(def.qualified_name())),
             hash_map::Entry::Vacant(ve) => {
+                self.locations.insert(ve.key().clone(), def.location.clone());
                 ve.insert(def)
             },
         };
@@ -106,7 +322,12 @@ impl ModelBuilder {
         Ok(())
     }
 
-    pub(crate) fn check(self) -> Result<NobleIdlModel, CheckError> {
+    pub(crate) fn locations(&self) -> &HashMap<QualifiedName, SourceLocation> {
+        &self.locations
+    }
+
+    pub(crate) fn check(self) -> Result<CheckedModel, CheckError> {
+        let mut warnings = Vec::new();
         let mut types = HashSet::new();
         let mut definitions = HashMap::new();
 
@@ -118,28 +339,69 @@ impl ModelBuilder {
 
 		phase1::run(&mut definitions, &types)?;
 
+		let mut annotation_scope_entries = self.annotation_scopes;
+		phase1::resolve_annotation_scopes(&mut annotation_scope_entries, &types)?;
+		let annotation_scopes = annotation_scopes::declarations(annotation_scope_entries)?;
+
+		let mut annotation_registry = self.annotation_registry;
+		for decl in &annotation_scopes {
+			annotation_registry.declare(decl.scope.clone());
+		}
+
+		let mut package_annotations = self.package_annotations;
+
 
 		let mut definitions: HashMap<_, _> = definitions.into_iter()
 			.map(|(k, v)| (k, v.into_api()))
 			.collect();
 
+		annotation_scopes::run(&definitions, &package_annotations, &annotation_registry, &mut warnings)?;
+
 		phase2::run(&definitions)?;
 
 		let phase3_state = phase3::run(&mut definitions)?;
-		let phase4_state = phase4::run(&mut definitions, &phase3_state)?;
+		let phase4_state = phase4::run(&mut definitions, &package_annotations, &phase3_state)?;
+
+		// Library definitions are not linted, so ignored esexpr annotations in them are still errors.
+		for (name, path) in &phase4_state.esexpr_without_derive {
+			if definitions.get(name).is_some_and(|dfn| dfn.is_library) {
+				return Err(CheckError::ESExprAnnotationWithoutDerive(name.clone(), path.clone()));
+			}
+		}
 
 		let mut tag_scan_state = TagScannerState {
 			tags: HashMap::new(),
 		};
 		phase5::run(&mut definitions, &mut tag_scan_state)?;
 		phase6::run(&definitions, &phase4_state, &mut tag_scan_state)?;
-		phase7::run(&mut definitions);
+		annotation_scopes::decode(&mut definitions, &mut package_annotations, &annotation_scopes, &mut tag_scan_state)?;
+		deprecation::run(&mut definitions, &mut warnings);
+
+		let mut packages = package_annotations.into_iter()
+			.map(|(name, annotations)| PackageInfo {
+				name: Box::new(name),
+				annotations: annotations.into_iter().map(Box::new).collect(),
+			})
+			.collect_vec();
+
+		phase7::run(&mut definitions, &mut packages);
 
         let mut model_definitions = definitions.into_values().map(Box::new).collect_vec();
 		model_definitions.sort_by_key(|dfn| dfn.name.clone());
 
-        Ok(NobleIdlModel {
-            definitions: model_definitions,
+		packages.retain(|package| !package.annotations.is_empty());
+		packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(CheckedModel {
+            model: NobleIdlModel {
+                definitions: model_definitions,
+                packages: packages.into_iter().map(Box::new).collect(),
+                annotation_scopes: annotation_scopes.into_iter().map(Box::new).collect(),
+            },
+            warnings,
+            files: self.files,
+            esexpr_without_derive: phase4_state.esexpr_without_derive,
+            locations: self.locations,
         })
     }
 }
@@ -35,6 +35,25 @@ pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, type_names:
 	Ok(())
 }
 
+// Annotation scope value types are resolved in the scope of the file that declares them.
+pub fn resolve_annotation_scopes(annotation_scopes: &mut [AnnotationScopeEntry], type_names: &HashSet<QualifiedName>) -> Result<(), CheckError> {
+	let model_types = ModelTypes {
+		type_names,
+	};
+
+	for entry in annotation_scopes {
+		let scope = GlobalScope {
+			package: &entry.package,
+			imports: &entry.imports,
+			types: &model_types,
+		};
+
+		check_type(&scope, &mut entry.declaration.value_type)?;
+	}
+
+	Ok(())
+}
+
 
 
 pub trait TypeScope {
@@ -281,41 +300,45 @@ impl <'a, Scope: TypeScope + Copy> ModelChecker<'a, Scope> {
 	}
 
 	fn check_type(&self, t: &mut TypeExpr) -> Result<(), CheckError> {
-		let mut t2 = TypeExpr::InvalidType;
-		std::mem::swap(&mut t2, t);
-		match self.check_type_impl(t2) {
-			TypeResult::Success(t2) => {
-				*t = t2;
-				Ok(())
-			},
-			TypeResult::Failure(t2, e) => {
-				*t = t2;
-				Err(e)
-			},
-		}
+		check_type(&self.scope, t)
 	}
 
-	fn check_type_impl(&self, t: TypeExpr) -> TypeResult {
-		match t {
-			TypeExpr::InvalidType => panic!("Unexpected invalid type"),
+}
 
-			TypeExpr::UnresolvedName(name, mut args) => {
-				for arg in &mut args {
-					if let Some(e) = self.check_type(arg).err() {
-						return TypeResult::Failure(TypeExpr::UnresolvedName(name, args), e);
-					}
-				}
+fn check_type(scope: &impl TypeScope, t: &mut TypeExpr) -> Result<(), CheckError> {
+	let mut t2 = TypeExpr::InvalidType;
+	std::mem::swap(&mut t2, t);
+	match check_type_impl(scope, t2) {
+		TypeResult::Success(t2) => {
+			*t = t2;
+			Ok(())
+		},
+		TypeResult::Failure(t2, e) => {
+			*t = t2;
+			Err(e)
+		},
+	}
+}
+
+fn check_type_impl(scope: &impl TypeScope, t: TypeExpr) -> TypeResult {
+	match t {
+		TypeExpr::InvalidType => panic!("Unexpected invalid type"),
 
-				match self.scope.resolve_type(name, args) {
-					Ok(t) => TypeResult::Success(t),
-					Err(e) => TypeResult::Failure(TypeExpr::InvalidType, e)
+		TypeExpr::UnresolvedName(name, mut args) => {
+			for arg in &mut args {
+				if let Some(e) = check_type(scope, arg).err() {
+					return TypeResult::Failure(TypeExpr::UnresolvedName(name, args), e);
 				}
-			},
+			}
 
-			TypeExpr::DefinedType(..) | TypeExpr::TypeParameter { .. } => TypeResult::Success(t),
-		}
-	}
+			match scope.resolve_type(name, args) {
+				Ok(t) => TypeResult::Success(t),
+				Err(e) => TypeResult::Failure(TypeExpr::InvalidType, e)
+			}
+		},
 
+		TypeExpr::DefinedType(..) | TypeExpr::TypeParameter { .. } => TypeResult::Success(t),
+	}
 }
 
 enum TypeResult {
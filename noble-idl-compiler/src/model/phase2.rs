@@ -20,6 +20,7 @@ pub fn run(definitions: &HashMap<QualifiedName, api::DefinitionInfo>) -> Result<
 		let checker = ModelChecker {
 			scope,
 			definition_name,
+			definition: def,
 		};
 
 		checker.check_type_parameters(TypeParamOwner::Type, &def.type_parameters)?;
@@ -90,6 +91,7 @@ enum TypeParamOwner {
 struct ModelChecker<'a, Scope> {
 	scope: Scope,
 	definition_name: &'a QualifiedName,
+	definition: &'a api::DefinitionInfo,
 }
 
 impl <'a, Scope: TypeScope<'a> + Copy + 'a> ModelChecker<'a, Scope> {
@@ -100,6 +102,7 @@ impl <'a, Scope: TypeScope<'a> + Copy + 'a> ModelChecker<'a, Scope> {
 				type_parameters,
 			},
 			definition_name: self.definition_name,
+			definition: self.definition,
 		}
 	}
 
@@ -206,6 +209,8 @@ impl <'a, Scope: TypeScope<'a> + Copy + 'a> ModelChecker<'a, Scope> {
 			api::TypeExpr::DefinedType(name, args) => {
 				let dfn = self.scope.get_definition(name.as_ref())?;
 
+				self.check_visibility(dfn)?;
+
 				if dfn.type_parameters.len() != args.len() {
 					return Err(CheckError::TypeParameterMismatch { expected: dfn.type_parameters.len(), actual: args.len() });
 				}
@@ -221,6 +226,23 @@ impl <'a, Scope: TypeScope<'a> + Copy + 'a> ModelChecker<'a, Scope> {
 		Ok(())
 	}
 
+	fn check_visibility(&self, dfn: &api::DefinitionInfo) -> Result<(), CheckError> {
+		if dfn.visibility != api::DefinitionVisibility::Internal {
+			return Ok(());
+		}
+
+		// Internal definitions from a library belong to another crate.
+		if dfn.is_library && !self.definition.is_library {
+			return Err(CheckError::InternalTypeNotAccessible(self.definition_name.clone(), (*dfn.name).clone()));
+		}
+
+		if self.definition.visibility == api::DefinitionVisibility::Public {
+			return Err(CheckError::InternalTypeExposed(self.definition_name.clone(), (*dfn.name).clone()));
+		}
+
+		Ok(())
+	}
+
 	fn check_type_arg(&self, param: &api::TypeParameter, arg: &api::TypeExpr) -> Result<(), CheckError> {
 		self.check_type(arg)?;
 
@@ -11,6 +11,7 @@ pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>) -> Result<E
 		optional_container_types: HashMap::new(),
 		vararg_container_types: HashMap::new(),
 		dict_container_types: HashMap::new(),
+		esexpr_without_derive: Vec::new(),
 	};
 
 	for dfn in definitions.values_mut() {
@@ -24,6 +25,7 @@ pub struct ESExprOptionParseExtern {
 	pub optional_container_types: HashMap<QualifiedName, ContainerTypeMetadata>,
 	pub vararg_container_types: HashMap<QualifiedName, ContainerTypeMetadata>,
 	pub dict_container_types: HashMap<QualifiedName, ContainerTypeMetadata>,
+	pub esexpr_without_derive: Vec<(QualifiedName, Vec<String>)>,
 }
 
 impl ESExprOptionParseExtern {
@@ -131,7 +133,7 @@ impl ESExprOptionParseExtern {
 			}))
 		}
 		else if literals.is_some() {
-			return Err(CheckError::ESExprAnnotationWithoutDerive(def_name.clone(), vec![]));
+			self.esexpr_without_derive.push((def_name.clone(), vec![]));
 		}
 
 		Ok(())
@@ -6,12 +6,14 @@ use noble_idl_api::*;
 use super::CheckError;
 use super::phase3::{ContainerTypeMetadata, ESExprOptionParseExtern};
 
-pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, phase2_state: &ESExprOptionParseExtern) -> Result<ESExprOptionParserState, CheckError> {
+pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, package_annotations: &HashMap<PackageName, Vec<Annotation>>, phase2_state: &ESExprOptionParseExtern) -> Result<ESExprOptionParserState, CheckError> {
 	let mut parser = ESExprOptionParser {
 		optional_container_types: &phase2_state.optional_container_types,
 		vararg_container_types: &phase2_state.vararg_container_types,
 		dict_container_types: &phase2_state.dict_container_types,
+		package_derive_codec: scan_packages(package_annotations)?,
 		esexpr_codecs: HashMap::new(),
+		esexpr_without_derive: phase2_state.esexpr_without_derive.clone(),
 	};
 
 	for dfn in definitions.values_mut() {
@@ -20,6 +22,7 @@ pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, phase2_stat
 
 	let state = ESExprOptionParserState {
 		esexpr_codecs: parser.esexpr_codecs,
+		esexpr_without_derive: parser.esexpr_without_derive,
 	};
 
 	Ok(state)
@@ -27,16 +30,51 @@ pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, phase2_stat
 
 pub struct ESExprOptionParserState {
 	pub esexpr_codecs: HashMap<QualifiedName, bool>,
+	pub esexpr_without_derive: Vec<(QualifiedName, Vec<String>)>,
 }
 
 struct ESExprOptionParser<'a> {
 	optional_container_types: &'a HashMap<QualifiedName, ContainerTypeMetadata>,
 	vararg_container_types: &'a HashMap<QualifiedName, ContainerTypeMetadata>,
 	dict_container_types: &'a HashMap<QualifiedName, ContainerTypeMetadata>,
+	package_derive_codec: HashSet<PackageName>,
 	esexpr_codecs: HashMap<QualifiedName, bool>,
+
+	// Annotations on types without derive-codec are ignored and reported by the lint pass.
+	esexpr_without_derive: Vec<(QualifiedName, Vec<String>)>,
+}
+
+fn scan_packages(package_annotations: &HashMap<PackageName, Vec<Annotation>>) -> Result<HashSet<PackageName>, CheckError> {
+	let mut package_derive_codec = HashSet::new();
+
+	for (package, annotations) in package_annotations {
+		for ann in annotations {
+			if ann.scope != "esexpr" {
+				continue;
+			}
+
+			let esexpr_pkg = EsexprAnnPackage::decode_esexpr(ann.value.clone())
+				.map_err(|e| CheckError::InvalidPackageESExprAnnotation(package.clone(), e))?;
+
+			match esexpr_pkg {
+				EsexprAnnPackage::DeriveCodec => {
+					if !package_derive_codec.insert(package.clone()) {
+						return Err(CheckError::DuplicatePackageESExprAnnotation(package.clone(), "derive-codec".to_owned()));
+					}
+				},
+			}
+		}
+	}
+
+	Ok(package_derive_codec)
 }
 
 impl <'a> ESExprOptionParser<'a> {
+	// A derive-codec or no-derive-codec annotation on the definition overrides the package default.
+	fn has_derive_codec(&self, def_name: &QualifiedName, explicit_derive_codec: Option<bool>) -> bool {
+		explicit_derive_codec.unwrap_or_else(|| self.package_derive_codec.contains(def_name.package_name()))
+	}
+
 	fn scan_definition(&mut self, dfn: &mut DefinitionInfo) -> Result<(), CheckError> {
 		match dfn.definition.as_mut() {
 			Definition::Record(rec) =>
@@ -59,7 +97,7 @@ impl <'a> ESExprOptionParser<'a> {
 	}
 
 	fn scan_record(&mut self, def_name: &QualifiedName, annotations: &[Box<Annotation>], rec: &mut RecordDefinition) -> Result<(), CheckError> {
-		let mut has_derive_codec = false;
+		let mut explicit_derive_codec = None;
 		let mut constructor = None;
 		for ann in annotations {
 			if ann.scope != "esexpr" {
@@ -70,12 +108,12 @@ impl <'a> ESExprOptionParser<'a> {
 				.map_err(|e| CheckError::InvalidESExprAnnotation(def_name.clone(), e))?;
 
 			match esexpr_rec {
-				EsexprAnnRecord::DeriveCodec => {
-					if has_derive_codec {
+				EsexprAnnRecord::DeriveCodec | EsexprAnnRecord::NoDeriveCodec => {
+					if explicit_derive_codec.is_some() {
 						return Err(CheckError::DuplicateESExprAnnotation(def_name.clone(), vec![], "derive-codec".to_owned()));
 					}
 
-					has_derive_codec = true;
+					explicit_derive_codec = Some(esexpr_rec == EsexprAnnRecord::DeriveCodec);
 				},
 				EsexprAnnRecord::Constructor(constructor_name) => {
 					if constructor.is_some() {
@@ -87,8 +125,10 @@ impl <'a> ESExprOptionParser<'a> {
 			}
 		}
 
+		let has_derive_codec = self.has_derive_codec(def_name, explicit_derive_codec);
+
 		if !has_derive_codec && constructor.is_some() {
-			return Err(CheckError::ESExprAnnotationWithoutDerive(def_name.clone(), vec![]));
+			self.esexpr_without_derive.push((def_name.clone(), vec![]));
 		}
 
 		if has_derive_codec {
@@ -106,7 +146,7 @@ impl <'a> ESExprOptionParser<'a> {
 	}
 
 	fn scan_enum(&mut self, def_name: &QualifiedName, annotations: &[Box<Annotation>], e: &mut EnumDefinition) -> Result<(), CheckError> {
-		let mut has_derive_codec = false;
+		let mut explicit_derive_codec = None;
 		for ann in annotations {
 			if ann.scope != "esexpr" {
 				continue;
@@ -116,16 +156,18 @@ impl <'a> ESExprOptionParser<'a> {
 				.map_err(|e| CheckError::InvalidESExprAnnotation(def_name.clone(), e))?;
 
 			match esexpr_rec {
-				EsexprAnnEnum::DeriveCodec => {
-					if has_derive_codec {
+				EsexprAnnEnum::DeriveCodec | EsexprAnnEnum::NoDeriveCodec => {
+					if explicit_derive_codec.is_some() {
 						return Err(CheckError::DuplicateESExprAnnotation(def_name.clone(), vec![], "derive-codec".to_owned()));
 					}
 
-					has_derive_codec = true;
+					explicit_derive_codec = Some(esexpr_rec == EsexprAnnEnum::DeriveCodec);
 				},
 			}
 		}
 
+		let has_derive_codec = self.has_derive_codec(def_name, explicit_derive_codec);
+
 		if has_derive_codec {
 			e.esexpr_options = Some(Box::new(EsexprEnumOptions {}));
 		}
@@ -139,7 +181,8 @@ impl <'a> ESExprOptionParser<'a> {
 				}
 
 				if !has_derive_codec {
-					return Err(CheckError::ESExprAnnotationWithoutDerive(def_name.clone(), vec![ c.name.clone() ]));
+					self.esexpr_without_derive.push((def_name.clone(), vec![ c.name.clone() ]));
+					continue;
 				}
 
 				let esexpr_rec = EsexprAnnEnumCase::decode_esexpr(ann.value.clone())
@@ -175,10 +218,6 @@ impl <'a> ESExprOptionParser<'a> {
 				}
 			}
 
-			if !has_derive_codec && (constructor.is_some() || has_inline_value) {
-				return Err(CheckError::ESExprAnnotationWithoutDerive(def_name.clone(), vec![]));
-			}
-
 			if has_derive_codec {
 				c.esexpr_options = Some(Box::new(EsexprEnumCaseOptions {
 					case_type:
@@ -198,7 +237,7 @@ impl <'a> ESExprOptionParser<'a> {
 	}
 
 	fn scan_simple_enum(&mut self, def_name: &QualifiedName, annotations: &[Box<Annotation>], e: &mut SimpleEnumDefinition) -> Result<(), CheckError> {
-		let mut has_derive_codec = false;
+		let mut explicit_derive_codec = None;
 		for ann in annotations {
 			if ann.scope != "esexpr" {
 				continue;
@@ -208,16 +247,18 @@ impl <'a> ESExprOptionParser<'a> {
 				.map_err(|e| CheckError::InvalidESExprAnnotation(def_name.clone(), e))?;
 
 			match esexpr_rec {
-				EsexprAnnSimpleEnum::DeriveCodec => {
-					if has_derive_codec {
+				EsexprAnnSimpleEnum::DeriveCodec | EsexprAnnSimpleEnum::NoDeriveCodec => {
+					if explicit_derive_codec.is_some() {
 						return Err(CheckError::DuplicateESExprAnnotation(def_name.clone(), vec![], "derive-codec".to_owned()));
 					}
 
-					has_derive_codec = true;
+					explicit_derive_codec = Some(esexpr_rec == EsexprAnnSimpleEnum::DeriveCodec);
 				},
 			}
 		}
 
+		let has_derive_codec = self.has_derive_codec(def_name, explicit_derive_codec);
+
 		if has_derive_codec {
 			e.esexpr_options = Some(Box::new(EsexprSimpleEnumOptions {}));
 		}
@@ -230,7 +271,8 @@ impl <'a> ESExprOptionParser<'a> {
 				}
 
 				if !has_derive_codec {
-					return Err(CheckError::ESExprAnnotationWithoutDerive(def_name.clone(), vec![ c.name.clone() ]));
+					self.esexpr_without_derive.push((def_name.clone(), vec![ c.name.clone() ]));
+					continue;
 				}
 
 				let esexpr_rec = EsexprAnnSimpleEnumCase::decode_esexpr(ann.value.clone())
@@ -247,10 +289,6 @@ impl <'a> ESExprOptionParser<'a> {
 				}
 			}
 
-			if !has_derive_codec && constructor.is_some() {
-				return Err(CheckError::ESExprAnnotationWithoutDerive(def_name.clone(), vec![]));
-			}
-
 			if has_derive_codec {
 				c.esexpr_options = Some(Box::new(EsexprSimpleEnumCaseOptions {
 					name: constructor.unwrap_or_else(|| c.name.clone()),
@@ -263,7 +301,7 @@ impl <'a> ESExprOptionParser<'a> {
 		Ok(())
 	}
 
-	fn scan_fields(&self, fields: &mut [Box<RecordField>], def_name: &QualifiedName, case_name: Option<&str>, is_esexpr_type: bool) -> Result<(), CheckError> {
+	fn scan_fields(&mut self, fields: &mut [Box<RecordField>], def_name: &QualifiedName, case_name: Option<&str>, is_esexpr_type: bool) -> Result<(), CheckError> {
 		let mut keywords = HashSet::new();
 
 		let mut has_dict = false;
@@ -293,7 +331,8 @@ impl <'a> ESExprOptionParser<'a> {
 					.map_err(|e| CheckError::InvalidESExprAnnotation(def_name.clone(), e))?;
 
 				if !is_esexpr_type {
-					return Err(CheckError::ESExprAnnotationWithoutDerive(def_name.clone(), current_path()))
+					self.esexpr_without_derive.push((def_name.clone(), current_path()));
+					continue;
 				}
 
 				match esexpr_field {
@@ -377,10 +416,6 @@ impl <'a> ESExprOptionParser<'a> {
 				return Err(CheckError::ESExprFieldIncompatibleOptions(def_name.clone(), case_name.map(str::to_owned), field.name.clone()));
 			}
 
-			if !is_esexpr_type && (is_keyword.is_some() || is_dict || is_vararg || is_optional || is_default_value.is_some()) {
-				return Err(CheckError::ESExprAnnotationWithoutDerive(def_name.clone(), vec![]));
-			}
-
 			if is_esexpr_type {
 				let kind =
 					if is_vararg {
@@ -5,6 +5,8 @@ use noble_idl_api::*;
 use noble_idl_runtime::Binary;
 use num_bigint::BigUint;
 
+use noble_idl_api::annotation_schema::AnnotationLocation;
+
 use super::{tag_scanner::{TagScanner, TagScannerState}, CheckError};
 
 
@@ -25,6 +27,27 @@ pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, tag_scan_st
 	Ok(())
 }
 
+// Decodes an annotation value using the esexpr options that were computed for the value type.
+pub fn decode_annotation_value<'a>(definitions: &'a HashMap<QualifiedName, DefinitionInfo>, tag_scan_state: &'a mut TagScannerState, location: &'a AnnotationLocation, scope: &'a str, t: &TypeExpr, value: ESExpr) -> Result<EsexprDecodedValue, CheckError> {
+	let mut parser = ESExprOptionDefaultValueParser {
+		definitions,
+		tag_scanner: TagScanner {
+			definitions,
+			state: tag_scan_state,
+		},
+		default_values: HashMap::new(),
+	};
+
+	let mut value_parser = ValueParser {
+		outer_parser: &mut parser,
+		seen_fields: HashSet::new(),
+
+		context: ValueContext::Annotation { location, scope },
+	};
+
+	value_parser.parse_value(t, value)
+}
+
 
 struct ESExprOptionDefaultValueParser<'a> {
 	definitions: &'a HashMap<QualifiedName, DefinitionInfo>,
@@ -91,9 +114,7 @@ impl <'a> ESExprOptionDefaultValueParser<'a> {
 								outer_parser: self,
 								seen_fields: HashSet::new(),
 
-								dfn,
-								case_name,
-								field,
+								context: ValueContext::DefaultValue { dfn, case_name, field },
 							};
 
 							let key = FieldKey {
@@ -121,14 +142,31 @@ struct ValueParser<'a, 'b> {
 	outer_parser: &'b mut ESExprOptionDefaultValueParser<'a>,
 	seen_fields: HashSet<FieldKey>,
 
-	dfn: &'a DefinitionInfo,
-	case_name: Option<&'a str>,
-	field: &'a RecordField,
+	context: ValueContext<'a>,
+}
+
+// Where the value being parsed came from, used for error reporting.
+enum ValueContext<'a> {
+	DefaultValue {
+		dfn: &'a DefinitionInfo,
+		case_name: Option<&'a str>,
+		field: &'a RecordField,
+	},
+	Annotation {
+		location: &'a AnnotationLocation,
+		scope: &'a str,
+	},
 }
 
 impl <'a, 'b> ValueParser<'a, 'b> {
 	fn error<S: Into<String>>(&self, message: S) -> CheckError {
-		CheckError::ESExprInvalidDefaultValue(message.into(), self.dfn.name.as_ref().clone(), self.case_name.map(str::to_owned), self.field.name.clone())
+		match &self.context {
+			ValueContext::DefaultValue { dfn, case_name, field } =>
+				CheckError::ESExprInvalidDefaultValue(message.into(), dfn.name.as_ref().clone(), case_name.map(str::to_owned), field.name.clone()),
+
+			ValueContext::Annotation { location, scope } =>
+				CheckError::InvalidAnnotation((*location).clone(), (*scope).to_owned(), message.into()),
+		}
 	}
 
 	fn fail<A, S: Into<String>>(&self, message: S) -> Result<A, CheckError> {
@@ -211,6 +249,29 @@ impl <'a, 'b> ValueParser<'a, 'b> {
 					if !tags.contains(&ESExprTag::Constructor(name.clone())) {
 						continue;
 					}
+
+					// The whole expression is the value of the field.
+					let mapping = dfn.type_parameters
+						.iter()
+						.map(|tp| tp.name())
+						.zip(type_args.iter().map(Box::as_ref))
+						.collect::<HashMap<_, _>>();
+
+					let mut field_type = field.field_type.as_ref().clone();
+					if !field_type.substitute(&mapping) {
+						self.fail("Could not substitute types in inline value field.")?;
+					}
+
+					let value = self.parse_value(&field_type, ESExpr::Constructor { name, args, kwargs })?;
+
+					return Ok(EsexprDecodedValue::Enum {
+						t: Box::new(t.clone()),
+						case_name: c.name.clone(),
+						fields: vec![Box::new(EsexprDecodedFieldValue {
+							name: field.name.clone(),
+							value: Box::new(value),
+						})],
+					});
 				},
 				EsexprEnumCaseType::Constructor(case_ctor_name) => {
 					if name != *case_ctor_name {
@@ -252,6 +313,11 @@ impl <'a, 'b> ValueParser<'a, 'b> {
 	}
 
 	fn parse_extern_type_value(&mut self, dfn: &'a DefinitionInfo, et: &'a ExternTypeDefinition, t: &TypeExpr, type_args: &[Box<TypeExpr>], mut value: ESExpr) -> Result<EsexprDecodedValue, CheckError> {
+		// Any expression is a valid value of the esexpr type.
+		if is_esexpr_type(&dfn.name) {
+			return Ok(EsexprDecodedValue::FromEsexpr { t: Box::new(t.clone()), value });
+		}
+
 		let Some(esexpr_options) = et.esexpr_options.as_ref() else { self.fail("Missing esexpr options")? };
 
 		let mapping = dfn.type_parameters
@@ -506,6 +572,11 @@ impl <'a, 'b> ValueParser<'a, 'b> {
 
 
 
+fn is_esexpr_type(name: &QualifiedName) -> bool {
+	name.0.0 == ["nobleidl", "core"] && name.1 == "esexpr"
+}
+
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct FieldKey {
 	definition_name: QualifiedName,
@@ -3,10 +3,14 @@ use std::collections::HashMap;
 use noble_idl_api::*;
 
 
-pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>) {
+pub fn run(definitions: &mut HashMap<QualifiedName, DefinitionInfo>, packages: &mut [PackageInfo]) {
 	for dfn in definitions.values_mut() {
 		remove_definition(dfn);
 	}
+
+	for package in packages {
+		package.annotations.retain(non_esexpr_ann);
+	}
 }
 
 
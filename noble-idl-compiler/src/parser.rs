@@ -1,5 +1,6 @@
 use crate::ast;
 
+use esexpr::{ESExpr, ESExprCodec};
 use esexpr_text::parser::{simple_identifier, expr};
 
 use nom::{
@@ -8,7 +9,7 @@ use nom::{
     branch::alt,
     character::complete::{alphanumeric1, multispace1},
     bytes::complete::{tag, take_until},
-    combinator::{all_consuming, map, not, opt, value, cut},
+    combinator::{all_consuming, map, map_opt, not, opt, value, cut},
     multi::{many0, many0_count, separated_list1},
     sequence::{delimited, pair, preceded, terminated},
 };
@@ -64,20 +65,71 @@ fn identifier(input: &str) -> IResult<&str, &str> {
 pub fn definition_file(input: &str) -> IResult<&str, ast::DefinitionFile> {
 	all_consuming(
 		map((
-			package_specifier,
-			many0(import),
-			many0(definition),
+			preceded(skip_ws, (remaining_len, package_specifier)),
+			many0(preceded(skip_ws, (remaining_len, import))),
+			many0(file_item),
 			skip_ws,
-		), |(package, imports, definitions, _)| {
+		), |((package_remaining, (package, package_annotations)), imports, items, _)| {
+			let package_location = source_location(input, package_remaining);
+			let (import_locations, imports) = imports.into_iter()
+				.map(|(remaining, import)| (source_location(input, remaining), import))
+				.unzip();
+
+			let mut definitions = Vec::new();
+			let mut definition_locations = Vec::new();
+			let mut annotation_scopes = Vec::new();
+
+			for item in items {
+				match item {
+					FileItem::Definition(remaining, def) => {
+						definitions.push(def);
+						definition_locations.push(source_location(input, remaining));
+					},
+					FileItem::AnnotationScope(decl) => annotation_scopes.push(decl),
+				}
+			}
+
 			ast::DefinitionFile {
 				package,
+				package_annotations,
+				package_location,
 				imports,
+				import_locations,
 				definitions,
+				definition_locations,
+				annotation_scopes,
 			}
 		})
 	).parse(input)
 }
 
+// Definitions store the length of the input remaining at their start, which is converted to a location once the whole file is known.
+enum FileItem {
+	Definition(usize, ast::Definition),
+	AnnotationScope(ast::AnnotationScopeDeclaration),
+}
+
+fn file_item(input: &str) -> IResult<&str, FileItem> {
+	alt((
+		map(annotation_scope, FileItem::AnnotationScope),
+		map(preceded(skip_ws, (remaining_len, definition)), |(remaining, def)| FileItem::Definition(remaining, def)),
+	)).parse(input)
+}
+
+fn remaining_len(input: &str) -> IResult<&str, usize> {
+	Ok((input, input.len()))
+}
+
+fn source_location(source: &str, remaining: usize) -> ast::SourceLocation {
+	let (line, column) = crate::line_column(&source[..source.len() - remaining]);
+
+	ast::SourceLocation {
+		file: None,
+		line,
+		column,
+	}
+}
+
 
 fn package_name(input: &str) -> IResult<&str, ast::PackageName> {
     map(separated_list1(
@@ -93,15 +145,16 @@ fn qual_name(input: &str) -> IResult<&str, ast::QualifiedName> {
     }).parse(input)
 }
 
-fn package_specifier(input: &str) -> IResult<&str, ast::PackageName> {
-    map(opt(delimited(
+fn package_specifier(input: &str) -> IResult<&str, (ast::PackageName, Vec<ast::Annotation>)> {
+    map(opt((
+            annotations,
             keyword("package"),
             cut(package_name),
             sym(";"),
     )), |pkg| {
         match pkg {
-            Some(pkg) => pkg,
-            None => ast::PackageName(vec!()),
+            Some((annotations, _, pkg, _)) => (pkg, annotations),
+            None => (ast::PackageName(vec!()), vec!()),
         }
     }).parse(input)
 }
@@ -115,18 +168,118 @@ fn import(input: &str) -> IResult<&str, ast::PackageName> {
 }
 
 fn annotations(input: &str) -> IResult<&str, Vec<ast::Annotation>> {
-    many0(
+    many0(alt((
+        deprecated_marker,
         map((
             sym("@"),
             cut(identifier),
             cut(sym(":")),
 			skip_ws,
             cut(expr),
-        ), |(_, scope, _, _, value)| ast::Annotation { scope: scope.to_owned(), value })
+        ), |(_, scope, _, _, value)| ast::Annotation { scope: scope.to_owned(), value, decoded_value: None }),
+    ))).parse(input)
+}
+
+// @deprecated("message", since: "version") is stored as an annotation in the deprecated scope.
+fn deprecated_marker(input: &str) -> IResult<&str, ast::Annotation> {
+    map(
+        preceded(
+            (
+                sym("@"),
+                keyword("deprecated"),
+                not(tag("-")),
+                not(sym(":")),
+            ),
+            opt(delimited(
+                sym("("),
+                cut(deprecation_args),
+                cut(sym(")")),
+            )),
+        ),
+        |args| {
+            let (message, since) = args.unwrap_or_default();
+            ast::Annotation {
+                scope: "deprecated".to_owned(),
+                value: ast::Deprecation { message, since }.encode_esexpr(),
+                decoded_value: None,
+            }
+        },
     ).parse(input)
 }
 
+fn deprecation_args(input: &str) -> IResult<&str, (Option<String>, Option<String>)> {
+    alt((
+        map(
+            pair(
+                string_literal,
+                opt(preceded((sym(","), keyword("since"), cut(sym(":"))), cut(string_literal))),
+            ),
+            |(message, since)| (Some(message), since),
+        ),
+        map(
+            preceded((keyword("since"), cut(sym(":"))), cut(string_literal)),
+            |since| (None, Some(since)),
+        ),
+    )).parse(input)
+}
+
+fn string_literal(input: &str) -> IResult<&str, String> {
+    map_opt(
+        preceded(skip_ws, expr),
+        |value| match value {
+            ESExpr::Str(s) => Some(s),
+            _ => None,
+        },
+    ).parse(input)
+}
+
+fn annotation_scope(input: &str) -> IResult<&str, ast::AnnotationScopeDeclaration> {
+    map((
+        keyword("annotation"),
+        cut(keyword("scope")),
+        cut(identifier),
+        cut(keyword("for")),
+        cut(annotation_target),
+        cut(sym(":")),
+        cut(type_expr),
+        cut(sym(";")),
+    ), |(_, _, scope, _, target, _, value_type, _)| ast::AnnotationScopeDeclaration {
+        scope: scope.to_owned(),
+        target,
+        value_type,
+    }).parse(input)
+}
+
+// Longer names are listed first because keyword only checks for a following alphanumeric character.
+fn annotation_target(input: &str) -> IResult<&str, ast::AnnotationTarget> {
+    alt((
+        value(ast::AnnotationTarget::SimpleEnumCase, keyword("simple-enum-case")),
+        value(ast::AnnotationTarget::SimpleEnum, keyword("simple-enum")),
+        value(ast::AnnotationTarget::EnumCase, keyword("enum-case")),
+        value(ast::AnnotationTarget::Enum, keyword("enum")),
+        value(ast::AnnotationTarget::RecordField, keyword("record-field")),
+        value(ast::AnnotationTarget::Record, keyword("record")),
+        value(ast::AnnotationTarget::ExternType, keyword("extern-type")),
+        value(ast::AnnotationTarget::InterfaceMethod, keyword("interface-method")),
+        value(ast::AnnotationTarget::Interface, keyword("interface")),
+        value(ast::AnnotationTarget::MethodParameter, keyword("method-parameter")),
+        value(ast::AnnotationTarget::ExceptionType, keyword("exception-type")),
+        value(ast::AnnotationTarget::TypeParameter, keyword("type-parameter")),
+        value(ast::AnnotationTarget::Package, keyword("package")),
+    )).parse(input)
+}
+
+
 
+fn visibility(input: &str) -> IResult<&str, ast::DefinitionVisibility> {
+    map(
+        opt(keyword("internal")),
+        |internal| match internal {
+            Some(_) => ast::DefinitionVisibility::Internal,
+            None => ast::DefinitionVisibility::Public,
+        },
+    ).parse(input)
+}
 
 fn definition(input: &str) -> IResult<&str, ast::Definition> {
     alt((
@@ -143,18 +296,20 @@ fn definition(input: &str) -> IResult<&str, ast::Definition> {
 fn record_def(input: &str) -> IResult<&str, ast::RecordDefinition> {
     map((
         annotations,
+        visibility,
         keyword("record"),
         cut(identifier),
         type_parameters,
         cut(sym("{")),
         many0(record_field),
         cut(sym("}")),
-    ), |(annotations, _, name, type_parameters, _, fields, _)| {
+    ), |(annotations, visibility, _, name, type_parameters, _, fields, _)| {
         ast::RecordDefinition {
             name: name.to_owned(),
             type_parameters,
             fields,
             annotations,
+            visibility,
         }
     }).parse(input)
 }
@@ -178,6 +333,7 @@ fn record_field(input: &str) -> IResult<&str, ast::RecordField> {
 fn enum_def(input: &str) -> IResult<&str, ast::EnumDefinition> {
     map((
         annotations,
+        visibility,
         keyword("enum"),
         cut(identifier),
         type_parameters,
@@ -185,12 +341,13 @@ fn enum_def(input: &str) -> IResult<&str, ast::EnumDefinition> {
         separated_list1(sym(","), enum_case),
         opt(sym(",")),
         cut(sym("}")),
-    ), |(annotations, _, name, type_parameters, _, cases, _, _)| {
+    ), |(annotations, visibility, _, name, type_parameters, _, cases, _, _)| {
         ast::EnumDefinition {
             name: name.to_owned(),
             type_parameters,
             cases,
             annotations,
+            visibility,
         }
     }).parse(input)
 }
@@ -220,6 +377,7 @@ fn enum_case_body(input: &str) -> IResult<&str, Vec<ast::RecordField>> {
 fn simple_enum_def(input: &str) -> IResult<&str, ast::SimpleEnumDefinition> {
     map((
         annotations,
+        visibility,
         keyword("simple"),
         keyword("enum"),
         cut(identifier),
@@ -227,11 +385,12 @@ fn simple_enum_def(input: &str) -> IResult<&str, ast::SimpleEnumDefinition> {
         separated_list1(sym(","), simple_enum_case),
         opt(sym(",")),
         cut(sym("}")),
-    ), |(annotations, _, _, name, _, cases, _, _)| {
+    ), |(annotations, visibility, _, _, name, _, cases, _, _)| {
         ast::SimpleEnumDefinition {
             name: name.to_owned(),
             cases,
             annotations,
+            visibility,
         }
     }).parse(input)
 }
@@ -251,16 +410,18 @@ fn simple_enum_case(input: &str) -> IResult<&str, ast::SimpleEnumCase> {
 fn extern_type(input: &str) -> IResult<&str, ast::ExternTypeDefinition> {
     map((
         annotations,
+        visibility,
         keyword("extern"),
         keyword("type"),
         cut(identifier),
         type_parameters,
         sym(";"),
-    ), |(annotations, _, _, name, type_parameters, _)| {
+    ), |(annotations, visibility, _, _, name, type_parameters, _)| {
         ast::ExternTypeDefinition {
             name: name.to_owned(),
             type_parameters,
             annotations,
+            visibility,
         }
     }).parse(input)
 }
@@ -268,18 +429,20 @@ fn extern_type(input: &str) -> IResult<&str, ast::ExternTypeDefinition> {
 fn interface_def(input: &str) -> IResult<&str, ast::InterfaceDefinition> {
     map((
         annotations,
+        visibility,
         keyword("interface"),
         cut(identifier),
         type_parameters,
         cut(sym("{")),
         many0(interface_method),
         cut(sym("}")),
-    ), |(annotations, _, name, type_parameters, _, methods, _)| {
+    ), |(annotations, visibility, _, name, type_parameters, _, methods, _)| {
         ast::InterfaceDefinition {
             name: name.to_owned(),
             type_parameters,
             methods,
             annotations,
+            visibility,
         }
     }).parse(input)
 }
@@ -341,16 +504,18 @@ fn method_parameter(input: &str) -> IResult<&str, ast::InterfaceMethodParameter>
 fn exception_type_def(input: &str) -> IResult<&str, ast::ExceptionTypeDefinition> {
     map((
         annotations,
+        visibility,
         keyword("exception"),
         cut(identifier),
         keyword("of"),
         type_expr,
         sym(";"),
-    ), |(annotations, _, name, _, information, _)| {
+    ), |(annotations, visibility, _, name, _, information, _)| {
         ast::ExceptionTypeDefinition {
             name: name.to_owned(),
             information,
             annotations,
+            visibility,
         }
     }).parse(input)
 }
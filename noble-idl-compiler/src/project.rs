@@ -0,0 +1,184 @@
+use std::{ffi::OsString, path::{Path, PathBuf}};
+
+use derive_more::From;
+use esexpr::{ESExpr, ESExprCodec};
+
+use crate::{backends::{backend_plugin, BackendError}, discovery::{discover_files, input_dirs, DiscoveryError, Excludes}, lint::LintConfiguration, PluginInvocation, ProcessPlugin};
+
+pub const MANIFEST_FILE_NAME: &str = "noble-idl.esx";
+
+
+#[derive(ESExprCodec, Debug, Clone)]
+#[constructor = "noble-idl-project"]
+pub struct ProjectManifest {
+    #[keyword]
+    #[default_value = "Vec::<String>::new()"]
+    pub inputs: Vec<String>,
+
+    #[keyword]
+    #[default_value = "Vec::<String>::new()"]
+    pub libraries: Vec<String>,
+
+    #[keyword]
+    #[default_value = "Vec::<String>::new()"]
+    pub excludes: Vec<String>,
+
+    #[keyword]
+    #[default_value = "Vec::<PluginConfiguration>::new()"]
+    pub plugins: Vec<PluginConfiguration>,
+
+    #[keyword]
+    #[default_value = "LintConfiguration::default()"]
+    pub lints: LintConfiguration,
+}
+
+#[derive(ESExprCodec, Debug, Clone, PartialEq)]
+#[constructor = "plugin"]
+pub struct PluginConfiguration {
+    #[keyword]
+    pub name: String,
+
+    #[keyword]
+    #[optional]
+    pub command: Option<String>,
+
+    #[keyword]
+    #[optional]
+    pub backend: Option<String>,
+
+    #[keyword]
+    #[default_value = "Vec::<String>::new()"]
+    pub arguments: Vec<String>,
+
+    #[keyword]
+    #[optional]
+    pub options: Option<String>,
+
+    #[keyword]
+    #[optional]
+    pub language_options: Option<ESExpr>,
+
+    #[keyword]
+    #[optional]
+    pub output_dir: Option<String>,
+}
+
+#[derive(From, Debug)]
+pub enum ProjectError {
+    IOError(std::io::Error),
+    #[from(ignore)]
+    ParseError(PathBuf, String),
+    DecodeError(Box<esexpr::DecodeError>),
+    DiscoveryError(DiscoveryError),
+    BackendError(BackendError),
+    #[from(ignore)]
+    MissingPluginOptions(String),
+    #[from(ignore)]
+    MissingPluginCommand(String),
+}
+
+impl From<esexpr::DecodeError> for ProjectError {
+    fn from(value: esexpr::DecodeError) -> Self {
+        ProjectError::DecodeError(Box::new(value))
+    }
+}
+
+
+pub struct Project {
+    pub manifest_path: PathBuf,
+    pub library_files: Vec<PathBuf>,
+    pub files: Vec<PathBuf>,
+    pub library_dirs: Vec<PathBuf>,
+    pub input_dirs: Vec<PathBuf>,
+    pub excludes: Excludes,
+    pub plugins: Vec<PluginInvocation<ProcessPlugin>>,
+    pub lints: LintConfiguration,
+    pub config_files: Vec<PathBuf>,
+}
+
+pub fn find_manifest(start_dir: &Path) -> Option<PathBuf> {
+    start_dir.ancestors()
+        .map(|dir| dir.join(MANIFEST_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+pub fn load_project(manifest_path: &Path) -> Result<Project, ProjectError> {
+    let manifest = read_esexpr(manifest_path)?;
+    let manifest = ProjectManifest::decode_esexpr(manifest)?;
+
+    let base_dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let excludes = Excludes::new(base_dir, &manifest.excludes)?;
+
+    let mut config_files = vec![ manifest_path.to_owned() ];
+
+    Ok(Project {
+        manifest_path: manifest_path.to_owned(),
+        library_files: discover_files(base_dir, &manifest.libraries, &excludes)?,
+        files: discover_files(base_dir, &manifest.inputs, &excludes)?,
+        library_dirs: input_dirs(base_dir, &manifest.libraries),
+        input_dirs: input_dirs(base_dir, &manifest.inputs),
+        excludes,
+        plugins: manifest.plugins.into_iter()
+            .map(|plugin| plugin.load(base_dir, &mut config_files))
+            .collect::<Result<_, _>>()?,
+        lints: manifest.lints,
+        config_files,
+    })
+}
+
+pub fn load_plugin_configuration(path: &Path, config_files: &mut Vec<PathBuf>) -> Result<PluginInvocation<ProcessPlugin>, ProjectError> {
+    let config = read_esexpr(path)?;
+    let config = PluginConfiguration::decode_esexpr(config)?;
+    config_files.push(path.to_owned());
+    config.load(path.parent().unwrap_or(Path::new("")), config_files)
+}
+
+pub fn read_esexpr(path: &Path) -> Result<ESExpr, ProjectError> {
+    let s = std::fs::read_to_string(path)?;
+    esexpr_text::parse(&s).map_err(|e| ProjectError::ParseError(path.to_owned(), format!("{:?}", e)))
+}
+
+impl PluginConfiguration {
+    // Any options file that is read is added to config_files.
+    pub fn load(self, base_dir: &Path, config_files: &mut Vec<PathBuf>) -> Result<PluginInvocation<ProcessPlugin>, ProjectError> {
+        let plugin_options = match (self.language_options, self.options) {
+            (Some(language_options), _) => language_options,
+            (None, Some(options)) => {
+                let options_path = base_dir.join(options);
+                let options = read_esexpr(&options_path)?;
+                config_files.push(options_path);
+                options
+            },
+            (None, None) => return Err(ProjectError::MissingPluginOptions(self.name)),
+        };
+
+        let plugin_arguments = self.arguments.into_iter().map(OsString::from).collect();
+
+        let plugin = match (self.command, self.backend) {
+            (Some(command), _) => {
+                // Commands given as a path are relative to the configuration; bare names are looked up on PATH.
+                let plugin_command =
+                    if command.contains('/') || command.contains(std::path::MAIN_SEPARATOR) {
+                        base_dir.join(&command).into_os_string()
+                    }
+                    else {
+                        OsString::from(command)
+                    };
+
+                ProcessPlugin {
+                    plugin_command,
+                    plugin_arguments,
+                }
+            },
+            (None, Some(backend)) => backend_plugin(&backend, plugin_arguments)?,
+            (None, None) => return Err(ProjectError::MissingPluginCommand(self.name)),
+        };
+
+        Ok(PluginInvocation {
+            name: self.name,
+            plugin,
+            plugin_options,
+            output_dir: self.output_dir.map(|dir| base_dir.join(dir)),
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use derive_more::From;
+use esexpr::{ESExpr, ESExprCodec};
+use noble_idl_api::{check_protocol_version, NobleIDLPluginExecutor, NobleIdlGenerationRequest, NobleIdlGenerationResult, ProtocolError};
+use wasmtime::{Engine, Linker, Module, Store};
+use wasmtime_wasi::{pipe::{MemoryInputPipe, MemoryOutputPipe}, preview1::WasiP1Ctx, I32Exit, WasiCtxBuilder};
+
+// Upper bound on the size of the encoded generation result.
+const MAX_OUTPUT_SIZE: usize = 1 << 30;
+
+// Runs a backend compiled as a WASI preview 1 command.
+// The module uses the same protocol as ProcessPlugin over stdin and stdout, but has no access to the file system,
+// so generated files must be returned as file contents.
+pub struct WasmPlugin {
+    engine: Engine,
+    module: Module,
+    pub plugin_arguments: Vec<String>,
+}
+
+impl WasmPlugin {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, WasmPluginError> {
+        let engine = Engine::default();
+        let module = Module::from_file(&engine, path)?;
+        Ok(WasmPlugin {
+            engine,
+            module,
+            plugin_arguments: Vec::new(),
+        })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WasmPluginError> {
+        let engine = Engine::default();
+        let module = Module::new(&engine, bytes)?;
+        Ok(WasmPlugin {
+            engine,
+            module,
+            plugin_arguments: Vec::new(),
+        })
+    }
+
+    fn run(&self, input: Vec<u8>) -> Result<Vec<u8>, WasmPluginError> {
+        let stdout = MemoryOutputPipe::new(MAX_OUTPUT_SIZE);
+
+        let wasi = WasiCtxBuilder::new()
+            .arg("noble-idl-plugin")
+            .args(&self.plugin_arguments)
+            .stdin(MemoryInputPipe::new(input))
+            .stdout(stdout.clone())
+            .inherit_stderr()
+            .build_p1();
+
+        let mut linker: Linker<WasiP1Ctx> = Linker::new(&self.engine);
+        wasmtime_wasi::preview1::add_to_linker_sync(&mut linker, |ctx| ctx)?;
+
+        let mut store = Store::new(&self.engine, wasi);
+        let instance = linker.instantiate(&mut store, &self.module)?;
+        let start = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+
+        match start.call(&mut store, ()) {
+            Ok(()) => {},
+            Err(e) => match e.downcast_ref::<I32Exit>() {
+                Some(I32Exit(0)) => {},
+                Some(I32Exit(code)) => return Err(WasmPluginError::ExitCode(*code)),
+                None => return Err(WasmPluginError::WasmError(e)),
+            },
+        }
+
+        drop(store);
+
+        Ok(stdout.contents().to_vec())
+    }
+}
+
+impl NobleIDLPluginExecutor for WasmPlugin {
+    type LanguageOptions = ESExpr;
+    type Error = WasmPluginError;
+
+    fn generate(&self, request: NobleIdlGenerationRequest<Self::LanguageOptions>) -> Result<NobleIdlGenerationResult, Self::Error> {
+        let offered_capabilities = request.capabilities.clone();
+
+        let mut request_bytes = Vec::new();
+        let mut gen = esexpr_binary::ExprGenerator::new(&mut request_bytes);
+        gen.generate(&request.encode_esexpr())?;
+
+        let result_bytes = self.run(request_bytes)?;
+
+        let mut results = esexpr_binary::parse(&result_bytes[..])
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let num_res = results.len();
+        let res = match results.pop() {
+            Some(res) if num_res == 1 => res,
+            _ => return Err(WasmPluginError::UnexpectedGenerationResult {
+                expected_count: 1,
+                actual_count: num_res,
+            }),
+        };
+
+        check_protocol_version(&res)?;
+        let res = NobleIdlGenerationResult::decode_esexpr(res)?;
+        res.check_capabilities(&offered_capabilities)?;
+        Ok(res)
+    }
+}
+
+#[derive(From, Debug)]
+pub enum WasmPluginError {
+    WasmError(wasmtime::Error),
+    ESExprParseError(esexpr_binary::ParseError),
+    ESExprGeneratorError(esexpr_binary::GeneratorError),
+    ESExprDecodeError(esexpr::DecodeError),
+    ProtocolError(ProtocolError),
+    #[from(ignore)]
+    ExitCode(i32),
+    UnexpectedGenerationResult {
+        expected_count: usize,
+        actual_count: usize,
+    },
+}
@@ -0,0 +1,102 @@
+use std::{collections::{HashMap, HashSet}, path::{Path, PathBuf}, sync::mpsc::{Receiver, RecvTimeoutError}, time::Duration};
+
+use noble_idl_compiler::{discovery::{Excludes, IDL_EXTENSION}, ParseCache};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::{load_build, manifest_path, run_build, CommandLineOptions};
+
+pub fn watch(args: &CommandLineOptions) -> ! {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).expect("Could not create file watcher.");
+    let mut watched_dirs = HashMap::<PathBuf, RecursiveMode>::new();
+    let mut watched_files = HashSet::new();
+    let mut input_dirs = Vec::new();
+    let mut excludes = Excludes::default();
+    let mut cache = ParseCache::default();
+
+    loop {
+        // Configuration files named on the command line are watched even if they currently fail to load.
+        let mut files = args.plugin_configs.iter()
+            .chain(&args.plugin_options)
+            .cloned()
+            .chain(manifest_path(args))
+            .collect::<Vec<_>>();
+
+        match load_build(args) {
+            Ok(build) => {
+                run_build(&build, &mut cache);
+
+                files.extend(build.library_files);
+                files.extend(build.files);
+                files.extend(build.config_files);
+                input_dirs = build.input_dirs;
+                excludes = build.excludes;
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                files.extend(watched_files.iter().cloned());
+            },
+        }
+
+        watched_files = files.iter()
+            .map(|file| absolute_path(file))
+            .collect::<HashSet<_>>();
+
+        // Input directories are watched recursively so that new files are found.
+        let mut dirs = watched_files.iter()
+            .filter_map(|file| file.parent().map(|dir| (dir.to_owned(), RecursiveMode::NonRecursive)))
+            .collect::<HashMap<_, _>>();
+
+        dirs.extend(input_dirs.iter().map(|dir| (absolute_path(dir), RecursiveMode::Recursive)));
+
+        for (dir, mode) in &watched_dirs {
+            if dirs.get(dir) != Some(mode) {
+                let _ = watcher.unwatch(dir);
+            }
+        }
+
+        for (dir, mode) in &dirs {
+            if watched_dirs.get(dir) != Some(mode) {
+                if let Err(e) = watcher.watch(dir, *mode) {
+                    eprintln!("Could not watch {}: {:?}", dir.display(), e);
+                }
+            }
+        }
+
+        watched_dirs = dirs;
+
+        println!("Watching for changes...");
+        wait_for_change(&rx, &watched_files, &excludes);
+    }
+}
+
+fn absolute_path(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_owned())
+}
+
+fn wait_for_change(rx: &Receiver<notify::Result<notify::Event>>, watched_files: &HashSet<PathBuf>, excludes: &Excludes) {
+    let is_relevant = |event: &notify::Event| {
+        !matches!(event.kind, EventKind::Access(_)) &&
+            event.paths.iter().any(|path| {
+                watched_files.contains(path) ||
+                    (path.extension().is_some_and(|ext| ext == IDL_EXTENSION) && !excludes.is_excluded(path))
+            })
+    };
+
+    loop {
+        match rx.recv() {
+            Ok(Ok(event)) if is_relevant(&event) => break,
+            Ok(_) => {},
+            Err(_) => std::process::exit(1),
+        }
+    }
+
+    // Editors often write a file in several steps, so wait for the changes to settle.
+    loop {
+        match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(_) => {},
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => std::process::exit(1),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use noble_idl_api::{NobleIdlCompileErrorKind, NobleIdlCompileModelOptions, NobleIdlCompileModelResult};
+use noble_idl_compiler::{check_files, lint::LintConfiguration, nobleidl_compile_model, nobleidl_free, CompileModelError};
+use esexpr::ESExprCodec;
+
+const SOURCE: &str = r#"package check.test;
+
+import nobleidl.core;
+
+record point {
+    x: int;
+    y: int;
+}
+
+record line {
+    start: point;
+    start: point;
+}
+"#;
+
+#[test]
+fn check_errors_have_the_definition_location() {
+    let sources = common::write_sources(None, SOURCE);
+    let path = &sources.files[0];
+
+    let Err(e) = check_files::<CompileModelError>(&sources.library_files, &sources.files, &LintConfiguration::default()) else {
+        panic!("Expected a check error");
+    };
+
+    let [error] = &e.into_compile_errors()[..] else { panic!("Expected a single error") };
+
+    assert_eq!(error.kind, NobleIdlCompileErrorKind::CheckError);
+    assert_eq!(error.message, "Duplicate field check.test.line.start");
+    assert_eq!(error.file.as_deref(), Some(path.to_str().unwrap()));
+    assert_eq!(error.span.as_ref().map(|span| (span.start_line, span.start_column)), Some((10, 1)));
+    assert_eq!(error.to_string(), format!("{}:10:1: Duplicate field check.test.line.start", path.display()));
+}
+
+// The compile model result keeps the error messages as strings so that existing hosts can decode it.
+#[test]
+fn compile_model_returns_error_messages() {
+    let options = NobleIdlCompileModelOptions {
+        library_files: vec![common::CORE_LIBRARY.to_owned()],
+        files: vec![SOURCE.to_owned()],
+    };
+
+    let mut options_bytes = Vec::new();
+    esexpr_binary::ExprGenerator::new(&mut options_bytes).generate(&options.encode_esexpr()).unwrap();
+
+    let result_bytes = unsafe {
+        let mut result_size = 0;
+        let result = nobleidl_compile_model(options_bytes.as_mut_ptr(), options_bytes.len(), &mut result_size);
+        let result_bytes = std::slice::from_raw_parts(result, result_size).to_vec();
+        nobleidl_free(result, result_size);
+        result_bytes
+    };
+
+    let result = esexpr_binary::parse(&result_bytes[..]).next().unwrap().unwrap();
+    let NobleIdlCompileModelResult::Failure { errors } = NobleIdlCompileModelResult::decode_esexpr(result).unwrap() else {
+        panic!("Expected compilation to fail");
+    };
+
+    assert_eq!(errors, vec![ "10:1: Duplicate field check.test.line.start" ]);
+}
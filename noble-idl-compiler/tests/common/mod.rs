@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+pub const CORE_LIBRARY: &str = include_str!("../../../langs/noble-idl/runtime/nobleidl-core.nidl");
+
+#[allow(dead_code)]
+pub struct Sources {
+    _dir: tempfile::TempDir,
+    pub library_files: Vec<PathBuf>,
+    pub files: Vec<PathBuf>,
+}
+
+// Writes the core library, an optional library and the source to a temporary directory.
+#[allow(dead_code)]
+pub fn write_sources(library: Option<&str>, source: &str) -> Sources {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut library_files = vec![ dir.path().join("core.nidl") ];
+    std::fs::write(&library_files[0], CORE_LIBRARY).unwrap();
+
+    if let Some(library) = library {
+        let path = dir.path().join("library.nidl");
+        std::fs::write(&path, library).unwrap();
+        library_files.push(path);
+    }
+
+    let path = dir.path().join("test.nidl");
+    std::fs::write(&path, source).unwrap();
+
+    Sources {
+        _dir: dir,
+        library_files,
+        files: vec![ path ],
+    }
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use noble_idl_api::{DefinitionInfo, Deprecation};
+use noble_idl_compiler::{check_files, lint::LintConfiguration, model::{CheckWarning, CheckedModel}, CompileModelError};
+
+fn check(library: Option<&str>, source: &str) -> CheckedModel {
+    let sources = common::write_sources(library, source);
+    check_files::<CompileModelError>(&sources.library_files, &sources.files, &LintConfiguration::default()).unwrap()
+}
+
+// Returns the definition and used type names with the message of each deprecated type use.
+fn deprecation_warnings(checked: &CheckedModel) -> Vec<(String, String, Option<String>)> {
+    checked.warnings.iter()
+        .filter_map(|warning| match warning {
+            CheckWarning::DeprecatedTypeUsed(dfn, used_type, message) => Some((dfn.name().to_owned(), used_type.name().to_owned(), message.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn definition<'a>(checked: &'a CheckedModel, name: &str) -> &'a DefinitionInfo {
+    checked.model.definitions.iter()
+        .find(|dfn| dfn.name.name() == name)
+        .unwrap()
+}
+
+fn deprecation(message: Option<&str>, since: Option<&str>) -> Deprecation {
+    Deprecation {
+        message: message.map(str::to_owned),
+        since: since.map(str::to_owned),
+    }
+}
+
+#[test]
+fn deprecated_type_use_warns() {
+    let checked = check(None, r#"package deprecation.test;
+
+@deprecated("Use new-thing instead.", since: "1.2")
+record old-thing {}
+
+record user {
+    thing: old-thing;
+}
+"#);
+
+    assert_eq!(deprecation_warnings(&checked), vec![
+        ("user".to_owned(), "old-thing".to_owned(), Some("Use new-thing instead.".to_owned())),
+    ]);
+}
+
+#[test]
+fn deprecated_users_are_skipped() {
+    let checked = check(None, r#"package deprecation.test;
+
+@deprecated
+record old-thing {}
+
+@deprecated
+record old-user {
+    thing: old-thing;
+}
+"#);
+
+    assert!(deprecation_warnings(&checked).is_empty());
+}
+
+// Library definitions are not reported, but uses of deprecated library types are.
+#[test]
+fn library_users_are_skipped() {
+    let library = r#"package deprecation.library;
+
+@deprecated
+record old-thing {}
+
+record library-user {
+    thing: old-thing;
+}
+"#;
+
+    let checked = check(Some(library), r#"package deprecation.test;
+
+import deprecation.library;
+
+record user {
+    user: library-user;
+}
+
+record direct-user {
+    thing: old-thing;
+}
+"#);
+
+    assert_eq!(deprecation_warnings(&checked), vec![
+        ("direct-user".to_owned(), "old-thing".to_owned(), None),
+    ]);
+}
+
+#[test]
+fn deprecation_markers_are_parsed() {
+    let checked = check(None, r#"package deprecation.test;
+
+import nobleidl.core;
+
+@deprecated
+record bare {}
+
+@deprecated("Message only.")
+record with-message {}
+
+@deprecated(since: "2.0")
+record with-since {}
+
+@deprecated("Message and version.", since: "2.1")
+record with-message-and-since {
+    @deprecated("Field message.")
+    field: string;
+}
+"#);
+
+    assert_eq!(definition(&checked, "bare").deprecated.as_deref(), Some(&deprecation(None, None)));
+    assert_eq!(definition(&checked, "with-message").deprecated.as_deref(), Some(&deprecation(Some("Message only."), None)));
+    assert_eq!(definition(&checked, "with-since").deprecated.as_deref(), Some(&deprecation(None, Some("2.0"))));
+
+    let dfn = definition(&checked, "with-message-and-since");
+    assert_eq!(dfn.deprecated.as_deref(), Some(&deprecation(Some("Message and version."), Some("2.1"))));
+    assert!(dfn.annotations.is_empty());
+
+    let noble_idl_api::Definition::Record(r) = dfn.definition.as_ref() else { panic!("Expected a record") };
+    assert_eq!(r.fields[0].deprecated.as_deref(), Some(&deprecation(Some("Field message."), None)));
+}
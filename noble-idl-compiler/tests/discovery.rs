@@ -0,0 +1,35 @@
+use noble_idl_compiler::{discovery::{input_dirs, Excludes}, project::{load_project, MANIFEST_FILE_NAME}};
+
+const MANIFEST: &str = r#"(noble-idl-project inputs: (list "src"))"#;
+
+// Excludes given on the command line are relative to the current directory, which is passed as the base directory,
+// while the files of a manifest are joined with the directory of the manifest.
+#[test]
+fn command_line_excludes_apply_to_manifest_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("src/generated")).unwrap();
+    std::fs::write(dir.path().join(MANIFEST_FILE_NAME), MANIFEST).unwrap();
+    std::fs::write(dir.path().join("src/a.nidl"), "package a;").unwrap();
+    std::fs::write(dir.path().join("src/generated/b.nidl"), "package b;").unwrap();
+
+    let excludes = Excludes::new(dir.path(), &["src/generated"]).unwrap();
+    let project = load_project(&dir.path().join(MANIFEST_FILE_NAME)).unwrap();
+
+    let files = project.files.into_iter()
+        .filter(|file| !excludes.is_excluded(file))
+        .collect::<Vec<_>>();
+
+    assert_eq!(files, vec![ dir.path().join("src/a.nidl") ]);
+}
+
+#[test]
+fn input_dirs_include_directories_and_glob_bases() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::create_dir_all(dir.path().join("lib/nested")).unwrap();
+    std::fs::write(dir.path().join("main.nidl"), "package main;").unwrap();
+
+    let dirs = input_dirs(dir.path(), &["src", "lib/*/*.nidl", "main.nidl"]);
+
+    assert_eq!(dirs, vec![ dir.path().join("src"), dir.path().join("lib") ]);
+}
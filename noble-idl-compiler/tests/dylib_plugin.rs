@@ -0,0 +1,90 @@
+#![cfg(not(target_family = "wasm"))]
+
+mod common;
+
+use std::path::PathBuf;
+
+use esexpr::ESExpr;
+use noble_idl_api::{NobleIDLPluginExecutor, NobleIdlCompileModelOptions, NobleIdlGenerationRequest};
+use noble_idl_compiler::{compile_model, generate, DylibPlugin, Error};
+
+const SOURCE: &str = r#"
+package dylib.test;
+
+import nobleidl.core;
+
+record point {
+    x: int;
+    y: int;
+}
+
+simple enum color {
+    red,
+    green,
+}
+"#;
+
+// Builds the plugin from tests/test_plugin into the target directory of this test.
+fn build_test_plugin() -> PathBuf {
+    let target_dir = std::env::current_exe().unwrap()
+        .ancestors()
+        .nth(3)
+        .unwrap()
+        .to_owned();
+
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "-p", "noble-idl-test-plugin", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .unwrap();
+
+    assert!(status.success(), "Could not build the test plugin");
+
+    target_dir
+        .join("debug")
+        .join(libloading::library_filename("noble_idl_test_plugin"))
+}
+
+#[test]
+fn dylib_plugin_generates_from_model() {
+    let plugin = unsafe { DylibPlugin::load(build_test_plugin()) }.unwrap();
+
+    let model = compile_model(NobleIdlCompileModelOptions {
+        library_files: vec![common::CORE_LIBRARY.to_owned()],
+        files: vec![SOURCE.to_owned()],
+    }).unwrap();
+
+    let result = plugin.generate(NobleIdlGenerationRequest::new(ESExpr::Bool(true), model)).unwrap();
+
+    let mut generated_files = result.generated_files;
+    generated_files.sort();
+
+    assert_eq!(generated_files, vec![ "dylib.test.color.txt", "dylib.test.point.txt" ]);
+}
+
+#[test]
+fn dylib_plugin_files_outside_the_output_dir_are_rejected() {
+    let plugin = unsafe { DylibPlugin::load(build_test_plugin()) }.unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("out");
+    let absolute_path = dir.path().join("absolute.txt");
+
+    for path in [ "../escape.txt".to_owned(), absolute_path.to_str().unwrap().to_owned() ] {
+        let model = compile_model(NobleIdlCompileModelOptions {
+            library_files: vec![common::CORE_LIBRARY.to_owned()],
+            files: vec![SOURCE.to_owned()],
+        }).unwrap();
+
+        let result = generate(&plugin, model, ESExpr::Str(path.clone()), Some(&output_dir));
+
+        match result {
+            Err(Error::InvalidGeneratedFilePath(p)) => assert_eq!(p, path),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    assert!(!dir.path().join("escape.txt").exists());
+    assert!(!absolute_path.exists());
+    assert!(!output_dir.exists());
+}
@@ -0,0 +1,65 @@
+use noble_idl_compiler::{format::format_source, CompileModelError};
+
+const UNFORMATTED: &str = r#"
+package format.test;
+import nobleidl.core;
+
+
+// Points are stored as a pair of ints.
+record point {
+x: int;
+        y: int; // trailing comment { not a bracket
+}
+@esexpr:(derive-codec)
+enum shape {
+  circle {
+    @esexpr:(keyword)
+        radius: int;
+  },
+     empty,
+}
+@esexpr:(default-value "a { string
+  spanning lines")
+extern type text;
+"#;
+
+const FORMATTED: &str = r#"package format.test;
+import nobleidl.core;
+
+// Points are stored as a pair of ints.
+record point {
+    x: int;
+    y: int; // trailing comment { not a bracket
+}
+@esexpr:(derive-codec)
+enum shape {
+    circle {
+        @esexpr:(keyword)
+        radius: int;
+    },
+    empty,
+}
+@esexpr:(default-value "a { string
+  spanning lines")
+extern type text;
+"#;
+
+fn format(source: &str) -> String {
+    format_source::<CompileModelError>(source).unwrap()
+}
+
+#[test]
+fn format_normalizes_indentation() {
+    assert_eq!(format(UNFORMATTED), FORMATTED);
+}
+
+#[test]
+fn format_is_idempotent() {
+    let formatted = format(UNFORMATTED);
+    assert_eq!(format(&formatted), formatted);
+}
+
+#[test]
+fn format_rejects_invalid_source() {
+    assert!(format_source::<CompileModelError>("package format.test;\nrecord {").is_err());
+}
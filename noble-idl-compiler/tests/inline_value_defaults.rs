@@ -0,0 +1,69 @@
+mod common;
+
+use noble_idl_api::*;
+use noble_idl_compiler::compile_model;
+
+const SOURCE: &str = r#"
+package inline.defaults;
+
+import nobleidl.core;
+
+@esexpr:(derive-codec)
+record point {
+    x: int;
+    y: int;
+}
+
+@esexpr:(derive-codec)
+enum shape {
+    @esexpr:(inline-value)
+    at-point {
+        p: point;
+    },
+    empty,
+}
+
+@esexpr:(derive-codec)
+record holder {
+    @esexpr:(keyword)
+    @esexpr:(default-value (point 1 2))
+    value: shape;
+}
+"#;
+
+fn decoded_int(value: &EsexprDecodedValue) -> i64 {
+    match value {
+        EsexprDecodedValue::FromInt { i, .. } => i.try_into().unwrap(),
+        _ => panic!("Expected an int value, got {:?}", value),
+    }
+}
+
+#[test]
+fn inline_value_default_is_the_field_value() {
+    let model = compile_model(NobleIdlCompileModelOptions {
+        library_files: vec![common::CORE_LIBRARY.to_owned()],
+        files: vec![SOURCE.to_owned()],
+    }).unwrap();
+
+    let holder = model.definitions.iter()
+        .find(|dfn| dfn.name.name() == "holder")
+        .unwrap();
+
+    let Definition::Record(rec) = holder.definition.as_ref() else { panic!("Expected a record") };
+    let options = rec.fields[0].esexpr_options.as_ref().unwrap();
+    let EsexprRecordFieldKind::Keyword(_, mode) = options.kind.as_ref() else { panic!("Expected a keyword field") };
+    let EsexprRecordKeywordMode::DefaultValue(value) = mode.as_ref() else { panic!("Expected a default value") };
+
+    let EsexprDecodedValue::Enum { case_name, fields, .. } = value.as_ref() else { panic!("Expected an enum value, got {:?}", value) };
+    assert_eq!(case_name, "at-point");
+
+    let [field] = &fields[..] else { panic!("Expected a single field, got {:?}", fields) };
+    assert_eq!(field.name, "p");
+
+    let EsexprDecodedValue::Record { fields: point_fields, .. } = field.value.as_ref() else { panic!("Expected a record value, got {:?}", field.value) };
+    let coords = point_fields.iter()
+        .map(|f| (f.name.as_str(), decoded_int(&f.value)))
+        .collect::<Vec<_>>();
+
+    assert_eq!(coords, vec![("x", 1), ("y", 2)]);
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use std::collections::HashMap;
+
+use noble_idl_compiler::{check_files, lint::{LintConfiguration, LintDiagnostic, LintLevel, LintRule}, model::{CheckError, CheckWarning}, CompileModelError, Error};
+
+// Returns the lint warnings, or the denied lints.
+fn lint_with(source: &str, lints: &LintConfiguration) -> Result<Vec<LintDiagnostic>, Vec<LintDiagnostic>> {
+    let sources = common::write_sources(None, source);
+    let checked = match check_files::<CompileModelError>(&sources.library_files, &sources.files, lints) {
+        Ok(checked) => checked,
+        Err(Error::LintError(diagnostics)) => return Err(diagnostics),
+        Err(e) => panic!("Unexpected error: {:?}", e),
+    };
+
+    Ok(
+        checked.warnings.into_iter()
+            .filter_map(|warning| match warning {
+                CheckWarning::Lint(diagnostic) => Some(diagnostic),
+                _ => None,
+            })
+            .collect()
+    )
+}
+
+fn lint(source: &str) -> Vec<LintDiagnostic> {
+    lint_with(source, &LintConfiguration::default()).unwrap()
+}
+
+fn rules(diagnostics: &[LintDiagnostic]) -> Vec<LintRule> {
+    diagnostics.iter().map(|diagnostic| diagnostic.rule).collect()
+}
+
+#[test]
+fn unused_import() {
+    let diagnostics = lint(r#"package lint.test;
+
+import nobleidl.core;
+
+record empty {}
+"#);
+
+    assert_eq!(rules(&diagnostics), vec![ LintRule::UnusedImport ]);
+
+    let location = diagnostics[0].location.as_ref().unwrap();
+    assert!(location.file.as_ref().unwrap().ends_with("test.nidl"));
+    assert_eq!((location.line, location.column), (3, 1));
+}
+
+// The grammar only accepts lowercase identifiers, so parsed sources cannot violate the convention.
+// Check that identifiers with digits and multiple segments are not reported.
+#[test]
+fn naming_convention() {
+    let diagnostics = lint(r#"package lint.test2-d;
+
+import nobleidl.core;
+
+record point2-d {
+    x-value: int;
+    y-2: int;
+}
+"#);
+
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn empty_interface() {
+    let diagnostics = lint(r#"package lint.test;
+
+interface nothing {
+}
+"#);
+
+    assert_eq!(rules(&diagnostics), vec![ LintRule::EmptyInterface ]);
+}
+
+#[test]
+fn recursive_record() {
+    let diagnostics = lint(r#"package lint.test;
+
+record a {
+    b: b;
+}
+
+record b {
+    a: a;
+}
+"#);
+
+    assert_eq!(rules(&diagnostics), vec![ LintRule::RecursiveRecord, LintRule::RecursiveRecord ]);
+}
+
+#[test]
+fn esexpr_without_derive_codec() {
+    let source = r#"package lint.test;
+
+@esexpr:(constructor "thing")
+record thing {}
+"#;
+
+    let Err(diagnostics) = lint_with(source, &LintConfiguration::default()) else {
+        panic!("Expected esexpr-without-derive-codec to be denied");
+    };
+    assert_eq!(rules(&diagnostics), vec![ LintRule::ESExprWithoutDeriveCodec ]);
+
+    let errors = Error::<CompileModelError>::LintError(diagnostics).into_compile_errors();
+    assert!(errors[0].file.as_ref().unwrap().ends_with("test.nidl"));
+    assert_eq!(errors[0].span.as_ref().map(|span| (span.start_line, span.start_column)), Some((3, 1)));
+
+    let lints = LintConfiguration {
+        levels: HashMap::from([ (LintRule::ESExprWithoutDeriveCodec.name().to_owned(), LintLevel::Warn) ]),
+    };
+    let diagnostics = lint_with(source, &lints).unwrap();
+    assert_eq!(rules(&diagnostics), vec![ LintRule::ESExprWithoutDeriveCodec ]);
+}
+
+#[test]
+fn esexpr_without_derive_codec_in_library_is_an_error() {
+    let library = r#"package lint.library;
+
+@esexpr:(constructor "thing")
+record thing {}
+"#;
+
+    let sources = common::write_sources(Some(library), "package lint.test;\n");
+    let result = check_files::<CompileModelError>(&sources.library_files, &sources.files, &LintConfiguration::default());
+
+    let Err(Error::ModelCheckError(CheckError::ESExprAnnotationWithoutDerive(name, _), location)) = result else {
+        panic!("Expected an esexpr annotation error");
+    };
+    assert_eq!(name.name(), "thing");
+    assert!(location.unwrap().file.unwrap().ends_with("library.nidl"));
+}
+
+#[test]
+fn shadowed_type() {
+    let diagnostics = lint(r#"package lint.test;
+
+record point {}
+
+record holder[point] {
+    p: point;
+}
+"#);
+
+    assert_eq!(rules(&diagnostics), vec![ LintRule::ShadowedType ]);
+}
+
+#[test]
+fn lints_can_be_allowed_on_definitions() {
+    let diagnostics = lint(r#"package lint.test;
+
+@lint:(allow "empty-interface")
+interface nothing {
+}
+
+interface also-nothing {
+}
+"#);
+
+    assert_eq!(rules(&diagnostics), vec![ LintRule::EmptyInterface ]);
+    assert!(diagnostics[0].message.contains("also-nothing"));
+}
+
+#[test]
+fn lints_can_be_allowed_on_packages() {
+    let diagnostics = lint(r#"@lint:(allow "unused-import" "empty-interface")
+package lint.test;
+
+import nobleidl.core;
+
+interface nothing {
+}
+"#);
+
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn lints_can_be_allowed_by_configuration() {
+    let lints = LintConfiguration {
+        levels: HashMap::from([ (LintRule::EmptyInterface.name().to_owned(), LintLevel::Allow) ]),
+    };
+
+    let diagnostics = lint_with(r#"package lint.test;
+
+interface nothing {
+}
+"#, &lints).unwrap();
+
+    assert!(diagnostics.is_empty());
+}
@@ -0,0 +1,13 @@
+use std::io::{Read, Write};
+
+use noble_idl_api::plugin_abi::generate_serialized;
+use noble_idl_test_plugin::TestPlugin;
+
+// The same plugin using the protocol of process plugins, which is also used by WebAssembly plugins.
+fn main() {
+    let mut request = Vec::new();
+    std::io::stdin().read_to_end(&mut request).unwrap();
+
+    let result = generate_serialized(&TestPlugin, &request);
+    std::io::stdout().write_all(&result).unwrap();
+}
@@ -0,0 +1,31 @@
+use esexpr::ESExpr;
+use noble_idl_api::{export_plugin, NobleIDLPluginExecutor, NobleIdlGeneratedFile, NobleIdlGenerationRequest, NobleIdlGenerationResult};
+
+// Reports a generated file for each definition without writing anything.
+// If the options are a string, the plugin instead returns the contents of a file with that path.
+pub struct TestPlugin;
+
+impl NobleIDLPluginExecutor for TestPlugin {
+    type LanguageOptions = ESExpr;
+    type Error = std::convert::Infallible;
+
+    fn generate(&self, request: NobleIdlGenerationRequest<Self::LanguageOptions>) -> Result<NobleIdlGenerationResult, Self::Error> {
+        if let ESExpr::Str(path) = request.language_options {
+            let mut result = NobleIdlGenerationResult::new(Vec::new());
+            result.file_contents.push(Box::new(NobleIdlGeneratedFile {
+                path,
+                content: "generated".to_owned(),
+            }));
+            return Ok(result);
+        }
+
+        let generated_files = request.model.definitions.iter()
+            .filter(|dfn| !dfn.is_library)
+            .map(|dfn| format!("{}.txt", dfn.name))
+            .collect();
+
+        Ok(NobleIdlGenerationResult::new(generated_files))
+    }
+}
+
+export_plugin!(TestPlugin);
@@ -0,0 +1,79 @@
+mod common;
+
+use noble_idl_api::NobleIdlCompileModelOptions;
+use noble_idl_compiler::{compile_model, Error};
+
+const LIBRARY: &str = r#"
+package visibility.library;
+
+import nobleidl.core;
+
+internal record hidden {
+    x: int;
+}
+"#;
+
+fn compile(library: &str, source: &str) -> Result<(), String> {
+    compile_model(NobleIdlCompileModelOptions {
+        library_files: vec![common::CORE_LIBRARY.to_owned(), library.to_owned()],
+        files: vec![source.to_owned()],
+    })
+        .map(|_| ())
+        .map_err(|e| match e {
+            Error::ModelCheckError(e, _) => e.to_string(),
+            e => panic!("Unexpected error: {:?}", e),
+        })
+}
+
+#[test]
+fn internal_types_can_be_used_by_internal_definitions() {
+    let source = r#"
+        package visibility.test;
+
+        import nobleidl.core;
+
+        internal record point {
+            x: int;
+        }
+
+        internal record holder {
+            p: point;
+        }
+    "#;
+
+    assert_eq!(compile(LIBRARY, source), Ok(()));
+}
+
+#[test]
+fn public_definitions_cannot_expose_internal_types() {
+    let source = r#"
+        package visibility.test;
+
+        import nobleidl.core;
+
+        internal record point {
+            x: int;
+        }
+
+        record holder {
+            p: point;
+        }
+    "#;
+
+    assert_eq!(compile(LIBRARY, source), Err("Public definition visibility.test.holder exposes internal type visibility.test.point".to_owned()));
+}
+
+#[test]
+fn internal_library_types_are_not_accessible() {
+    let source = r#"
+        package visibility.test;
+
+        import visibility.library;
+
+        internal record holder {
+            h: hidden;
+        }
+    "#;
+
+    assert_eq!(compile(LIBRARY, source), Err("Internal type visibility.library.hidden is not accessible from visibility.test.holder".to_owned()));
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "wasm-plugin")]
+
+use std::path::{Path, PathBuf};
+
+const SOURCE: &str = r#"
+package wasm.test;
+
+import nobleidl.core;
+
+record point {
+    x: int;
+    y: int;
+}
+"#;
+
+// Builds the stdio version of the plugin from tests/test_plugin as a WASI command.
+fn build_test_plugin() -> PathBuf {
+    let target_dir = std::env::current_exe().unwrap()
+        .ancestors()
+        .nth(3)
+        .unwrap()
+        .to_owned();
+
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "-p", "noble-idl-test-plugin", "--bin", "test-plugin-stdio", "--target", "wasm32-wasip1", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .unwrap();
+
+    assert!(status.success(), "Could not build the test plugin");
+
+    target_dir.join("wasm32-wasip1/debug/test-plugin-stdio.wasm")
+}
+
+#[test]
+fn cli_runs_wasm_plugins() {
+    let plugin = build_test_plugin();
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("test.nidl"), SOURCE).unwrap();
+    std::fs::write(dir.path().join("options.esx"), "(options)").unwrap();
+
+    let core_library = Path::new(env!("CARGO_MANIFEST_DIR")).join("../langs/noble-idl/runtime/nobleidl-core.nidl");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_noble-idl-compiler"))
+        .arg("--no-manifest")
+        .arg("-L").arg(core_library)
+        .arg("-i").arg(dir.path().join("test.nidl"))
+        .arg("-P").arg(plugin)
+        .arg("-c").arg(dir.path().join("options.esx"))
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert_eq!(stdout, "[default] Generated 1 files:\nwasm.test.point.txt\n");
+}